@@ -0,0 +1,168 @@
+//! Benchmarks of the zoom and viewport computations, and of the whole plugin
+//! with many cameras.
+//!
+//! Run them with `cargo bench`.
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::window::{WindowRef, WindowResolution};
+use bevy_pixel_camera::math::{compute_viewport, compute_zoom};
+use bevy_pixel_camera::{
+    resize_headless_window, spawn_headless_window, PixelCameraHeadlessPlugin, PixelCameraPlugin,
+    PixelViewport, PixelZoom,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// The window sizes used by the benchmarks, in logical pixels.
+fn window_sizes() -> Vec<Vec2> {
+    (0..64)
+        .map(|i| Vec2::new(640.0 + 23.0 * i as f32, 360.0 + 17.0 * i as f32))
+        .collect()
+}
+
+fn zoom_modes() -> Vec<(&'static str, PixelZoom)> {
+    vec![
+        (
+            "FitSize",
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+        ),
+        ("FitLargerDim", PixelZoom::FitLargerDim(320)),
+        (
+            "FitBest",
+            PixelZoom::FitBest(vec![
+                UVec2::new(320, 180),
+                UVec2::new(320, 240),
+                UVec2::new(256, 224),
+                UVec2::new(384, 216),
+            ]),
+        ),
+        (
+            "FitSizeFloat",
+            PixelZoom::FitSizeFloat {
+                width: 320,
+                height: 180,
+            },
+        ),
+        (
+            "FitSizeHybrid",
+            PixelZoom::FitSizeHybrid {
+                width: 320,
+                height: 180,
+                tolerance: 0.1,
+            },
+        ),
+    ]
+}
+
+fn bench_zoom(c: &mut Criterion) {
+    let sizes = window_sizes();
+    let mut group = c.benchmark_group("compute_zoom");
+    for (name, mode) in zoom_modes() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &mode, |b, mode| {
+            b.iter(|| {
+                for size in &sizes {
+                    black_box(compute_zoom(mode, black_box(*size)));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_viewport(c: &mut Criterion) {
+    let sizes = window_sizes();
+    let pixel_viewport = PixelViewport::default();
+    let mut group = c.benchmark_group("compute_viewport");
+    for (name, mode) in zoom_modes() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &mode, |b, mode| {
+            b.iter(|| {
+                for size in &sizes {
+                    let zoom = compute_zoom(mode, *size);
+                    black_box(compute_viewport(
+                        mode,
+                        &pixel_viewport,
+                        zoom,
+                        size.as_uvec2(),
+                        black_box(*size),
+                    ));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+/// A headless app with `windows` windows, each rendered by `cameras` cameras
+/// with a viewport.
+fn headless_app(windows: usize, cameras: usize) -> (App, Vec<Entity>) {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        PixelCameraHeadlessPlugin,
+        PixelCameraPlugin::default(),
+    ));
+    let mut window_entities = vec![spawn_headless_window(&mut app.world, 800.0, 600.0)];
+    for _ in 1..windows {
+        let window = Window {
+            resolution: WindowResolution::new(800.0, 600.0),
+            ..Default::default()
+        };
+        window_entities.push(app.world.spawn(window).id());
+    }
+    let modes = zoom_modes();
+    for (i, window) in window_entities.iter().enumerate() {
+        for j in 0..cameras {
+            let (_, mode) = &modes[(i + j) % modes.len()];
+            app.world.spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        target: RenderTarget::Window(WindowRef::Entity(*window)),
+                        order: j as isize,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                mode.clone(),
+                PixelViewport::default(),
+            ));
+        }
+    }
+    app.update();
+    (app, window_entities)
+}
+
+fn bench_plugin(c: &mut Criterion) {
+    let sizes = window_sizes();
+    let mut group = c.benchmark_group("plugin");
+    for (windows, cameras) in [(1, 1), (1, 16), (4, 4), (16, 16)] {
+        let parameter = format!("{windows}x{cameras}");
+
+        // Frames where nothing changes.
+        let (mut app, _) = headless_app(windows, cameras);
+        group.bench_function(BenchmarkId::new("idle", &parameter), |b| {
+            b.iter(|| app.update());
+        });
+
+        // Frames where all the windows are resized.
+        let (mut app, window_entities) = headless_app(windows, cameras);
+        let mut frame = 0;
+        group.bench_function(BenchmarkId::new("resize", &parameter), |b| {
+            b.iter(|| {
+                let size = sizes[frame % sizes.len()];
+                frame += 1;
+                for &window in &window_entities {
+                    resize_headless_window(&mut app.world, window, size.x, size.y);
+                }
+                app.update();
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_zoom, bench_viewport, bench_plugin);
+criterion_main!(benches);
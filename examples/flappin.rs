@@ -55,7 +55,7 @@ fn main() {
                     ..default()
                 }),
         )
-        .add_plugins(PixelCameraPlugin)
+        .add_plugins(PixelCameraPlugin::default())
         .insert_resource(Rng { mz: 0, mw: 0 })
         .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
         .insert_resource(FlapTimer(Timer::from_seconds(0.5, TimerMode::Once)))
@@ -142,7 +142,7 @@ fn setup(
             width: WIDTH as i32,
             height: HEIGHT as i32,
         },
-        PixelViewport,
+        PixelViewport::default(),
     ));
     // Deprecated:
     // commands.spawn(bevy_pixel_camera::PixelCameraBundle::from_resolution(
@@ -8,7 +8,7 @@ fn main() {
     App::new()
         .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.2)))
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
-        .add_plugins(PixelCameraPlugin)
+        .add_plugins(PixelCameraPlugin::default())
         .add_systems(Startup, setup)
         .add_systems(Update, bevy::window::close_on_esc)
         .run();
@@ -23,7 +23,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             width: WIDTH,
             height: HEIGHT,
         },
-        PixelViewport,
+        PixelViewport::default(),
     ));
 
     let mire_handle = asset_server.load("mire-64x64.png");
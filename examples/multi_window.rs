@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::window::{WindowRef, WindowResolution};
+use bevy_pixel_camera::{PixelCameraPlugin, PixelViewport, PixelZoom};
+
+const WIDTH: i32 = 320;
+const HEIGHT: i32 = 180;
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.2)))
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(PixelCameraPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_mire, bevy::window::close_on_esc))
+        .run();
+}
+
+#[derive(Component)]
+struct Mire;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // The game camera, in the primary window: the whole virtual resolution
+    // WIDTH x HEIGHT always fits inside the window.
+    commands.spawn((
+        Camera2dBundle::default(),
+        PixelZoom::FitSize {
+            width: WIDTH,
+            height: HEIGHT,
+        },
+        PixelViewport::default(),
+    ));
+
+    // A secondary debug window, with its own size and scale factor.
+    let debug_window = commands
+        .spawn(Window {
+            title: "Debug view".to_string(),
+            resolution: WindowResolution::new(480.0, 480.0).with_scale_factor_override(1.0),
+            ..Default::default()
+        })
+        .id();
+
+    // The debug camera shows the center of the world, zoomed in: it is
+    // configured independently of the game camera, from the size of its own
+    // window.
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Window(WindowRef::Entity(debug_window)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        PixelZoom::FitSize {
+            width: 80,
+            height: 80,
+        },
+        PixelViewport::default(),
+    ));
+
+    let mire_handle = asset_server.load("mire-64x64.png");
+
+    // A mire sprite moving around the center of the world.
+    commands.spawn((
+        SpriteBundle {
+            texture: mire_handle.clone(),
+            ..Default::default()
+        },
+        Mire,
+    ));
+
+    // Mire sprites in the corners of the virtual resolution.
+    for (x, y) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+        commands.spawn(SpriteBundle {
+            texture: mire_handle.clone(),
+            transform: Transform::from_xyz((x * WIDTH / 2) as f32, (y * HEIGHT / 2) as f32, 0.0),
+            ..Default::default()
+        });
+    }
+}
+
+fn move_mire(time: Res<Time>, mut mires: Query<&mut Transform, With<Mire>>) {
+    let t = time.elapsed_seconds();
+    for mut transform in &mut mires {
+        transform.translation.x = (24.0 * t.cos()).round();
+        transform.translation.y = (24.0 * t.sin()).round();
+    }
+}
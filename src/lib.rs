@@ -14,11 +14,12 @@
 //!
 //! There is two main methods to render pixel-art games: upscale each sprite
 //! independently, or render everything to an offscreen texture and only upscale
-//! this texture. This crate use the first method. There is advantages and
-//! drawbacks to both approaches.
+//! this texture. This crate use the first method by default, and provides the
+//! second one with the `PixelCanvasPlugin` (using the same `PixelZoom` modes).
+//! There is advantages and drawbacks to both approaches.
 //!
-//! Advantages of the "upscale each sprite independently" method (i.e. this
-//! crate):
+//! Advantages of the "upscale each sprite independently" method (i.e. the
+//! default):
 //!
 //! - allows for smoother scrolling and movement of sprites, if you're willing
 //!   to temporarily break the alignment on virtual pixels (this would be even
@@ -53,7 +54,7 @@
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
-//!         .add_plugins(PixelCameraPlugin)
+//!         .add_plugins(PixelCameraPlugin::default())
 //!         .add_systems(Startup, setup)
 //!         .run();
 //! }
@@ -68,7 +69,7 @@
 //!             width: 320,
 //!             height: 180,
 //!         },
-//!         PixelViewport,
+//!         PixelViewport::default(),
 //!     ));
 //!
 //!     commands.spawn(SpriteBundle {
@@ -105,11 +106,14 @@
 //! your virtual resolution, pass `true` to the `set_viewport` argument when
 //! creating the camera bundle (see example above).
 //!
+//! The deprecated `PixelBorderPlugin`, `PixelCameraBundle` and
+//! `PixelProjection` are only available with the `legacy` Cargo feature.
+//!
 //! ## Migration guide: 0.5 to 0.12 (Bevy 0.11 to 0.12)
 //!
 //! The `PixelCameraBundle` has been deprecated. Replace it with a standard
 //! `Camera2dBundle`, to which you add the `PixelZoom` and `PixelViewport`
-//! components.
+//! components (or with the `PixelCamera` bundle, which combines the three).
 //!
 //! # License
 //!
@@ -122,14 +126,101 @@
 //!
 //! at your option.
 
+pub mod math;
+mod pixel_alignment;
+mod pixel_background;
+#[cfg(feature = "legacy")]
 mod pixel_border;
+mod pixel_bounds;
+mod pixel_bundle;
+#[cfg(feature = "legacy")]
 mod pixel_camera;
+mod pixel_camera_info;
+mod pixel_camera_query;
+mod pixel_canvas;
+mod pixel_cinematic;
+mod pixel_commands;
+#[cfg(feature = "crt")]
+mod pixel_crt;
+mod pixel_cursor;
+#[cfg(feature = "debug-controls")]
+mod pixel_debug_controls;
+#[cfg(feature = "debug-overlay")]
+mod pixel_debug_overlay;
+mod pixel_default;
+mod pixel_dither;
+mod pixel_follow;
+mod pixel_headless;
+mod pixel_hires_overlay;
+mod pixel_letterbox;
+mod pixel_minimap;
+mod pixel_outline;
+mod pixel_palette;
+mod pixel_parallax;
 mod pixel_plugin;
+mod pixel_punch;
+#[cfg(feature = "recording")]
+mod pixel_recorder;
+mod pixel_rot_sprite;
+mod pixel_sampler;
+mod pixel_scanlines;
+mod pixel_shake;
+mod pixel_snap;
+#[cfg(feature = "text")]
+mod pixel_snap_text;
+mod pixel_split_screen;
+mod pixel_supersample;
+mod pixel_transition;
+mod pixel_upscale;
 mod pixel_zoom;
+mod post_process;
+mod render_target;
 
+pub use pixel_alignment::*;
+pub use pixel_background::*;
+#[cfg(feature = "legacy")]
 #[allow(deprecated)]
 pub use pixel_border::*;
+pub use pixel_bounds::*;
+pub use pixel_bundle::*;
+#[cfg(feature = "legacy")]
 #[allow(deprecated)]
 pub use pixel_camera::*;
+pub use pixel_camera_info::*;
+pub use pixel_camera_query::*;
+pub use pixel_canvas::*;
+pub use pixel_cinematic::*;
+pub use pixel_commands::*;
+#[cfg(feature = "crt")]
+pub use pixel_crt::*;
+pub use pixel_cursor::*;
+#[cfg(feature = "debug-controls")]
+pub use pixel_debug_controls::*;
+#[cfg(feature = "debug-overlay")]
+pub use pixel_debug_overlay::*;
+pub use pixel_default::*;
+pub use pixel_dither::*;
+pub use pixel_follow::*;
+pub use pixel_headless::*;
+pub use pixel_hires_overlay::*;
+pub use pixel_letterbox::*;
+pub use pixel_minimap::*;
+pub use pixel_outline::*;
+pub use pixel_palette::*;
+pub use pixel_parallax::*;
 pub use pixel_plugin::*;
+pub use pixel_punch::*;
+#[cfg(feature = "recording")]
+pub use pixel_recorder::*;
+pub use pixel_rot_sprite::*;
+pub use pixel_sampler::*;
+pub use pixel_scanlines::*;
+pub use pixel_shake::*;
+pub use pixel_snap::*;
+#[cfg(feature = "text")]
+pub use pixel_snap_text::*;
+pub use pixel_split_screen::*;
+pub use pixel_supersample::*;
+pub use pixel_transition::*;
+pub use pixel_upscale::*;
 pub use pixel_zoom::*;
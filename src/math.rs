@@ -0,0 +1,486 @@
+//! The zoom, viewport and snapping computations used by the plugin, exposed
+//! as pure functions.
+//!
+//! These functions don't need a window or a running app, so they can be used
+//! to predict what the plugin will do (for example in UI layout code or in
+//! unit tests).
+
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+
+use crate::{MarginUnit, PixelSafeArea, PixelViewport, PixelZoom, PixelZoomRounding, ViewportCrop};
+
+/// Compute the zoom that a camera with the specified `PixelZoom` mode would
+/// use for a render target of `logical_size` (in logical pixels), without any
+/// of the optional zoom components.
+pub fn compute_zoom(mode: &PixelZoom, logical_size: Vec2) -> f32 {
+    auto_zoom(mode, PixelZoomRounding::default(), false, logical_size)
+}
+
+/// Compute the viewport that a camera with the specified `PixelZoom` mode and
+/// `PixelViewport` would use for a render target of `physical_size` (in
+/// physical pixels) and `logical_size` (in logical pixels), with the specified
+/// `zoom`.
+pub fn compute_viewport(
+    mode: &PixelZoom,
+    pixel_viewport: &PixelViewport,
+    zoom: f32,
+    physical_size: UVec2,
+    logical_size: Vec2,
+) -> Viewport {
+    viewport(
+        mode,
+        pixel_viewport,
+        &PixelSafeArea::default(),
+        Vec2::splat(zoom),
+        physical_size,
+        logical_size,
+    )
+}
+
+/// Round `position` to the nearest multiple of `cell_size`.
+///
+/// Halfway values are always rounded up (towards positive infinity), so that
+/// the rounding behaves the same on both sides of the origin. This is the
+/// rounding used by `PixelSnap` and `SnapCameraToPixelGrid`.
+pub fn snap_to_grid(position: Vec2, cell_size: Vec2) -> Vec2 {
+    (position / cell_size + 0.5).floor() * cell_size
+}
+
+/// Round the x and y coordinates of `translation` to whole virtual pixels,
+/// where `pixel_size` is the size of a virtual pixel in world units (i.e. the
+/// scale of the orthographic projection of the camera, usually `1.0`). The z
+/// coordinate is left unchanged.
+pub fn snap_to_virtual_pixels(translation: Vec3, pixel_size: f32) -> Vec3 {
+    snap_to_grid(translation.truncate(), Vec2::splat(pixel_size)).extend(translation.z)
+}
+
+/// Extension trait to snap vectors to virtual pixels, with the same rounding
+/// as the plugin (see `snap_to_virtual_pixels`).
+pub trait PixelSnapExt {
+    /// Round the x and y coordinates to whole virtual pixels of `pixel_size`
+    /// world units.
+    fn pixel_snapped(self, pixel_size: f32) -> Self;
+}
+
+impl PixelSnapExt for Vec2 {
+    fn pixel_snapped(self, pixel_size: f32) -> Self {
+        snap_to_grid(self, Vec2::splat(pixel_size))
+    }
+}
+
+impl PixelSnapExt for Vec3 {
+    fn pixel_snapped(self, pixel_size: f32) -> Self {
+        snap_to_virtual_pixels(self, pixel_size)
+    }
+}
+
+pub(crate) fn auto_zoom(
+    mode: &PixelZoom,
+    rounding: PixelZoomRounding,
+    downscale: bool,
+    logical_size: Vec2,
+) -> f32 {
+    let ratio = |size: f32, target: i32| size.trunc() / (i32::max(target, 1) as f32);
+    let fit = |ratio: f32| {
+        if downscale && ratio > 0.0 && ratio < 1.0 {
+            1.0 / (1.0 / ratio).ceil()
+        } else {
+            f32::max(rounding.round(ratio), 1.0)
+        }
+    };
+    match mode {
+        PixelZoom::FitSize { width, height }
+        | PixelZoom::Expand {
+            min_width: width,
+            min_height: height,
+        } => {
+            let zoom_x = ratio(logical_size.x, *width);
+            let zoom_y = ratio(logical_size.y, *height);
+            fit(f32::min(zoom_x, zoom_y))
+        }
+        PixelZoom::FitWidth(width) => fit(ratio(logical_size.x, *width)),
+        PixelZoom::FitHeight(height) => fit(ratio(logical_size.y, *height)),
+        PixelZoom::FitLargerDim(length) => {
+            fit(ratio(f32::max(logical_size.x, logical_size.y), *length))
+        }
+        PixelZoom::FitBest(candidates) => match best_fit(candidates, logical_size) {
+            Some(best) => {
+                let zoom_x = ratio(logical_size.x, best.x as i32);
+                let zoom_y = ratio(logical_size.y, best.y as i32);
+                fit(f32::min(zoom_x, zoom_y))
+            }
+            None => 1.0,
+        },
+        PixelZoom::Fixed(zoom) => *zoom as f32,
+        PixelZoom::FitSizeFloat { width, height } => fractional_zoom(*width, *height, logical_size),
+        PixelZoom::FitSizeHybrid {
+            width,
+            height,
+            tolerance,
+        } => {
+            let zoom_x = ratio(logical_size.x, *width);
+            let zoom_y = ratio(logical_size.y, *height);
+            let integer_zoom = fit(f32::min(zoom_x, zoom_y));
+            let fractional_zoom = fractional_zoom(*width, *height, logical_size);
+            if 1.0 - integer_zoom / fractional_zoom > *tolerance {
+                fractional_zoom
+            } else {
+                integer_zoom
+            }
+        }
+        PixelZoom::Stretch { .. } => 1.0,
+    }
+}
+
+fn fractional_zoom(width: i32, height: i32, logical_size: Vec2) -> f32 {
+    let zoom_x = logical_size.x / (i32::max(width, 1) as f32);
+    let zoom_y = logical_size.y / (i32::max(height, 1) as f32);
+    let zoom = f32::min(zoom_x, zoom_y);
+    if zoom > 0.0 {
+        zoom
+    } else {
+        1.0
+    }
+}
+
+/// Select the candidate resolution whose aspect ratio is the closest to the
+/// aspect ratio of `size`.
+fn best_fit(candidates: &[UVec2], size: Vec2) -> Option<UVec2> {
+    let aspect_ratio = size.x / size.y;
+    candidates
+        .iter()
+        .filter(|candidate| candidate.x > 0 && candidate.y > 0)
+        .min_by(|a, b| {
+            let distance = |candidate: &UVec2| {
+                let candidate_ratio = (candidate.x as f32) / (candidate.y as f32);
+                (candidate_ratio / aspect_ratio).ln().abs()
+            };
+            distance(a).total_cmp(&distance(b))
+        })
+        .copied()
+}
+
+/// The area of the render target available for the viewport, in logical
+/// pixels (with the origin at the top-left corner).
+pub(crate) fn viewport_region(
+    pixel_viewport: Option<&PixelViewport>,
+    safe_area: &PixelSafeArea,
+    logical_size: Vec2,
+    zoom: Vec2,
+) -> Rect {
+    let mut region = Rect::from_corners(Vec2::ZERO, logical_size);
+    if let Some(pixel_viewport) = pixel_viewport {
+        let normalized = pixel_viewport
+            .region
+            .intersect(Rect::new(0.0, 0.0, 1.0, 1.0));
+        region = Rect::from_corners(normalized.min * logical_size, normalized.max * logical_size);
+        region.min = region.min.max(Vec2::new(safe_area.left, safe_area.top));
+        region.max = region
+            .max
+            .min(logical_size - Vec2::new(safe_area.right, safe_area.bottom));
+        let margins = &pixel_viewport.margins;
+        let scale = match margins.unit {
+            MarginUnit::LogicalPixels => Vec2::ONE,
+            MarginUnit::VirtualPixels => zoom,
+        };
+        region.min += Vec2::new(margins.left, margins.top) * scale;
+        region.max -= Vec2::new(margins.right, margins.bottom) * scale;
+        region.max = region.max.max(region.min);
+    }
+    region
+}
+
+/// The dimensions of the target resolution that constrain the viewport, in
+/// virtual pixels, for a render target (or region) of `available_size` logical
+/// pixels.
+pub(crate) fn target_dimensions(
+    mode: &PixelZoom,
+    available_size: Vec2,
+    zoom: Vec2,
+) -> (Option<i32>, Option<i32>) {
+    match mode {
+        PixelZoom::FitSize { width, height }
+        | PixelZoom::FitSizeFloat { width, height }
+        | PixelZoom::FitSizeHybrid { width, height, .. } => (Some(*width), Some(*height)),
+        PixelZoom::FitWidth(width) => (Some(*width), None),
+        PixelZoom::FitHeight(height) => (None, Some(*height)),
+        PixelZoom::FitLargerDim(length) => {
+            if available_size.x >= available_size.y {
+                (Some(*length), None)
+            } else {
+                (None, Some(*length))
+            }
+        }
+        PixelZoom::FitBest(candidates) => {
+            let pixel_aspect_ratio = zoom.x / zoom.y;
+            match best_fit(
+                candidates,
+                available_size / Vec2::new(pixel_aspect_ratio, 1.0),
+            ) {
+                Some(best) => (Some(best.x as i32), Some(best.y as i32)),
+                None => (None, None),
+            }
+        }
+        PixelZoom::Fixed(..) | PixelZoom::Expand { .. } | PixelZoom::Stretch { .. } => (None, None),
+    }
+}
+
+pub(crate) fn viewport(
+    mode: &PixelZoom,
+    pixel_viewport: &PixelViewport,
+    safe_area: &PixelSafeArea,
+    zoom: Vec2,
+    physical_size: UVec2,
+    logical_size: Vec2,
+) -> Viewport {
+    let region = viewport_region(Some(pixel_viewport), safe_area, logical_size, zoom);
+    let available_size = region.size();
+
+    let (auto_width, auto_height) = match (
+        pixel_viewport.crop,
+        target_dimensions(mode, available_size, zoom),
+    ) {
+        (ViewportCrop::PillarboxOnly, (width, _)) => (width, None),
+        (ViewportCrop::LetterboxOnly, (_, height)) => (None, height),
+        (ViewportCrop::Both, dimensions) => dimensions,
+    };
+
+    // Everything below is computed in physical pixels, with explicit rounding,
+    // so that the result does not depend on how the scale factor is
+    // represented.
+    let scale_factor = (physical_size.x as f32) / logical_size.x;
+    let alignment = pixel_viewport.alignment.as_vec();
+    let region_min = (region.min * scale_factor)
+        .round()
+        .as_uvec2()
+        .min(physical_size);
+    let region_max = (region.max * scale_factor)
+        .round()
+        .as_uvec2()
+        .min(physical_size);
+
+    // The position and length of the viewport along one axis. When `target`
+    // is specified, the viewport is cropped to `target` virtual pixels (or to
+    // the region if the zoom has been rounded up), and the remaining space is
+    // split according to the alignment.
+    let fit_axis = |target: Option<i32>, zoom: f32, start: u32, end: u32, alignment: f32| {
+        let available = end.saturating_sub(start);
+        let Some(target) = target else {
+            return (start, available);
+        };
+        let pixel = zoom * scale_factor;
+        let length = if pixel_viewport.snap_to_zoom {
+            // Only keep whole virtual pixels, so that no partial row or column
+            // is visible at the edges.
+            let count = f32::min(target as f32, ((available as f32) / pixel + 1.0e-4).floor());
+            (count * pixel).round() as u32
+        } else {
+            ((target as f32) * pixel) as u32
+        };
+        let length = u32::min(length, available);
+        let offset = ((available - length) as f32 * alignment).round() as u32;
+        (start + u32::min(offset, available - length), length)
+    };
+
+    let (viewport_x, viewport_width) =
+        fit_axis(auto_width, zoom.x, region_min.x, region_max.x, alignment.x);
+    let (viewport_y, viewport_height) =
+        fit_axis(auto_height, zoom.y, region_min.y, region_max.y, alignment.y);
+
+    // Shave the reserved strips off the edges of the viewport.
+    let reserved = &pixel_viewport.reserved;
+    let strip = |length: u32, zoom: f32| ((length as f32) * zoom * scale_factor).round() as u32;
+    let (left, right) = (strip(reserved.left, zoom.x), strip(reserved.right, zoom.x));
+    let (top, bottom) = (strip(reserved.top, zoom.y), strip(reserved.bottom, zoom.y));
+    let viewport_x = viewport_x + u32::min(left, viewport_width);
+    let viewport_y = viewport_y + u32::min(top, viewport_height);
+    let viewport_width = viewport_width.saturating_sub(left + right);
+    let viewport_height = viewport_height.saturating_sub(top + bottom);
+
+    Viewport {
+        physical_position: UVec2 {
+            x: viewport_x,
+            y: viewport_y,
+        },
+        physical_size: UVec2 {
+            x: u32::min(viewport_width, physical_size.x.saturating_sub(viewport_x)).max(1),
+            y: u32::min(viewport_height, physical_size.y.saturating_sub(viewport_y)).max(1),
+        },
+        depth: pixel_viewport.depth.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport_at(
+        mode: &PixelZoom,
+        pixel_viewport: &PixelViewport,
+        logical_size: Vec2,
+        scale_factor: f32,
+    ) -> Viewport {
+        let physical_size = (logical_size * scale_factor).round().as_uvec2();
+        let zoom = compute_zoom(mode, logical_size);
+        compute_viewport(mode, pixel_viewport, zoom, physical_size, logical_size)
+    }
+
+    #[test]
+    fn snapping_rounds_halfway_values_up() {
+        assert_eq!(Vec2::new(0.5, -0.5).pixel_snapped(1.0), Vec2::new(1.0, 0.0));
+        assert_eq!(
+            Vec2::new(1.49, -1.51).pixel_snapped(1.0),
+            Vec2::new(1.0, -2.0)
+        );
+        assert_eq!(
+            Vec3::new(3.0, 5.0, 7.5).pixel_snapped(2.0),
+            Vec3::new(4.0, 6.0, 7.5)
+        );
+    }
+
+    #[test]
+    fn fills_the_window_when_the_target_fits_exactly() {
+        let mode = PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        };
+        let viewport = viewport_at(
+            &mode,
+            &PixelViewport::default(),
+            Vec2::new(1600.0, 900.0),
+            1.25,
+        );
+        assert_eq!(viewport.physical_position, UVec2::ZERO);
+        assert_eq!(viewport.physical_size, UVec2::new(2000, 1125));
+    }
+
+    #[test]
+    fn centered_bars_are_symmetric() {
+        let mode = PixelZoom::FitSize {
+            width: 256,
+            height: 224,
+        };
+        for scale_factor in [1.0, 1.25, 1.5, 1.75, 2.0, 2.25] {
+            for logical_size in [
+                Vec2::new(1280.0, 720.0),
+                Vec2::new(1000.0, 600.0),
+                Vec2::new(1366.0, 768.0),
+                Vec2::new(801.0, 599.0),
+            ] {
+                let physical_size = (logical_size * scale_factor).round().as_uvec2();
+                let viewport =
+                    viewport_at(&mode, &PixelViewport::default(), logical_size, scale_factor);
+                let position = viewport.physical_position;
+                let end = position + viewport.physical_size;
+                let (left, right) = (position.x, physical_size.x - end.x);
+                let (top, bottom) = (position.y, physical_size.y - end.y);
+                assert!(
+                    left.abs_diff(right) <= 1 && top.abs_diff(bottom) <= 1,
+                    "asymmetric bars for {logical_size} at {scale_factor}: {viewport:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn size_is_a_whole_number_of_virtual_pixels() {
+        let mode = PixelZoom::FitSize {
+            width: 300,
+            height: 200,
+        };
+        let logical_size = Vec2::new(800.0, 600.0);
+        let physical_size = UVec2::new(1000, 750);
+
+        let snapped = compute_viewport(
+            &mode,
+            &PixelViewport::default(),
+            3.0,
+            physical_size,
+            logical_size,
+        );
+        // 266 virtual pixels of 3.75 physical pixels.
+        assert_eq!(snapped.physical_size, UVec2::new(998, 750));
+        assert_eq!(snapped.physical_position, UVec2::new(1, 0));
+
+        let truncated = compute_viewport(
+            &mode,
+            &PixelViewport {
+                snap_to_zoom: false,
+                ..Default::default()
+            },
+            3.0,
+            physical_size,
+            logical_size,
+        );
+        assert_eq!(truncated.physical_size, UVec2::new(1000, 750));
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let mode = PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        };
+        // 2.5 horizontally, 3.33 vertically: the smaller ratio is rounded.
+        let size = Vec2::new(800.0, 600.0);
+        assert_eq!(auto_zoom(&mode, PixelZoomRounding::Floor, false, size), 2.0);
+        assert_eq!(auto_zoom(&mode, PixelZoomRounding::Ceil, false, size), 3.0);
+        assert_eq!(
+            auto_zoom(&mode, PixelZoomRounding::Nearest, false, size),
+            3.0
+        );
+
+        let size = Vec2::new(760.0, 600.0);
+        assert_eq!(
+            auto_zoom(&mode, PixelZoomRounding::Nearest, false, size),
+            2.0
+        );
+    }
+
+    #[test]
+    fn rounding_exact_multiples_is_a_no_op() {
+        let mode = PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        };
+        let size = Vec2::new(1280.0, 720.0);
+        for rounding in [
+            PixelZoomRounding::Floor,
+            PixelZoomRounding::Ceil,
+            PixelZoomRounding::Nearest,
+        ] {
+            assert_eq!(auto_zoom(&mode, rounding, false, size), 4.0);
+        }
+    }
+
+    #[test]
+    fn ceil_only_fills_the_constraining_dimension() {
+        let mode = PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        };
+        let size = Vec2::new(1000.0, 500.0);
+        let zoom = auto_zoom(&mode, PixelZoomRounding::Ceil, false, size);
+        assert_eq!(zoom, 3.0);
+        // The height is cropped, but the width doesn't fill the window.
+        assert!(180.0 * zoom > size.y);
+        assert!(320.0 * zoom < size.x);
+    }
+
+    #[test]
+    fn rounding_never_goes_below_one() {
+        let mode = PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        };
+        let size = Vec2::new(200.0, 100.0);
+        assert_eq!(auto_zoom(&mode, PixelZoomRounding::Floor, false, size), 1.0);
+        assert_eq!(
+            auto_zoom(&mode, PixelZoomRounding::Nearest, false, size),
+            1.0
+        );
+        // Unless downscaling is allowed.
+        assert_eq!(auto_zoom(&mode, PixelZoomRounding::Floor, true, size), 0.5);
+    }
+}
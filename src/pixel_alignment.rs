@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::utils::HashSet;
+
+use crate::pixel_snap::virtual_pixel_size;
+use crate::{NoPixelSnap, PixelZoom};
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+/// Insert this resource to log a warning for each visible sprite that is not
+/// aligned on the virtual pixel grid, i.e. the usual causes of blurry or
+/// distorted pixel art:
+///
+/// - the global translation of the sprite is not a whole number of virtual
+///   pixels;
+/// - the sprite has an odd size (in virtual pixels) and is centered on that
+///   axis by its `Anchor` (the default), so its edges fall between two
+///   virtual pixels.
+///
+/// The check happens at the end of `PostUpdate`, after any snapping. Each
+/// sprite is only reported once. Sprites marked with `NoPixelSnap` are
+/// ignored.
+pub struct PixelAlignmentWarnings {
+    warned: HashSet<Entity>,
+}
+
+/// Tolerance of the alignment checks, in virtual pixels.
+const EPSILON: f32 = 1e-3;
+
+fn is_aligned(value: f32) -> bool {
+    (value - value.round()).abs() <= EPSILON
+}
+
+/// The size of a sprite, in world units (before scaling), if known.
+fn sprite_size(
+    sprite: &Sprite,
+    texture: &Handle<Image>,
+    atlas: Option<&TextureAtlas>,
+    images: &Assets<Image>,
+    layouts: &Assets<TextureAtlasLayout>,
+) -> Option<Vec2> {
+    if let Some(size) = sprite.custom_size {
+        return Some(size);
+    }
+    if let Some(rect) = sprite.rect {
+        return Some(rect.size());
+    }
+    if let Some(atlas) = atlas {
+        let layout = layouts.get(&atlas.layout)?;
+        return layout.textures.get(atlas.index).map(|rect| rect.size());
+    }
+    images.get(texture).map(|image| image.size_f32())
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_alignment_warnings_system(
+    warnings: Option<ResMut<PixelAlignmentWarnings>>,
+    cameras: Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+    sprites: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &Sprite,
+            &Handle<Image>,
+            Option<&TextureAtlas>,
+            Option<&Name>,
+        ),
+        (Without<NoPixelSnap>, Without<PixelZoom>),
+    >,
+    images: Res<Assets<Image>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+) {
+    let Some(mut warnings) = warnings else {
+        return;
+    };
+    let pixel_size = virtual_pixel_size(&cameras);
+    for (entity, global_transform, visibility, sprite, texture, atlas, name) in &sprites {
+        if !visibility.get() || warnings.warned.contains(&entity) {
+            continue;
+        }
+        let name = || {
+            name.map_or_else(
+                || format!("{entity:?}"),
+                |name| format!("{name} ({entity:?})"),
+            )
+        };
+
+        let translation = global_transform.translation().truncate() / pixel_size;
+        if !(is_aligned(translation.x) && is_aligned(translation.y)) {
+            warn!(
+                "sprite {} is not aligned on the virtual pixel grid: its translation is {} \
+                 virtual pixels (consider `PixelSnap` or `ImplicitPixelSnap`)",
+                name(),
+                translation,
+            );
+            warnings.warned.insert(entity);
+            continue;
+        }
+
+        let Some(size) = sprite_size(sprite, texture, atlas, &images, &layouts) else {
+            continue;
+        };
+        let (scale, ..) = global_transform.to_scale_rotation_translation();
+        let size = (size * scale.truncate() / pixel_size).round();
+        let anchor = sprite.anchor.as_vec();
+        let odd = |size: f32| size.rem_euclid(2.0) == 1.0;
+        let centered_x = odd(size.x) && anchor.x == 0.0;
+        let centered_y = odd(size.y) && anchor.y == 0.0;
+        if centered_x || centered_y {
+            warn!(
+                "sprite {} has an odd size ({} x {} virtual pixels) and a centered anchor, \
+                 so its edges are between virtual pixels (consider `Anchor::BottomLeft`, or \
+                 an even size)",
+                name(),
+                size.x,
+                size.y,
+            );
+            warnings.warned.insert(entity);
+        }
+    }
+
+    // Forget the despawned sprites.
+    if !warnings.warned.is_empty() {
+        warnings.warned.retain(|entity| sprites.contains(*entity));
+    }
+}
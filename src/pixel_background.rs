@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, ScalingMode};
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+
+use crate::pixel_default::HelperCamera;
+use crate::{NoPixelSnap, PixelZoom};
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Clear the whole render target of a `PixelZoom` camera (letterbox bars
+/// included) with a color or an image.
+///
+/// With this component, an additional camera is spawned, rendering to the same
+/// target before any other camera: its order is kept one below the lowest
+/// order of the other cameras rendering to that target. The image, if any, is
+/// stretched over the whole target, on the render layer `layer`. The
+/// background camera is despawned with the component.
+pub struct PixelBackground {
+    /// The color of the background.
+    pub color: Color,
+    /// The image drawn over the whole background, if any.
+    pub image: Option<Handle<Image>>,
+    /// The render layer used to draw the image.
+    pub layer: u8,
+    camera: Option<Entity>,
+}
+
+impl PixelBackground {
+    /// A background of the specified color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            image: None,
+            layer: RenderLayers::TOTAL_LAYERS as u8 - 5,
+            camera: None,
+        }
+    }
+
+    /// Also draw `image` over the whole background.
+    pub fn with_image(mut self, image: Handle<Image>) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    /// Draw the image on the specified render layer.
+    pub fn with_layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// The background camera, once spawned.
+    pub fn camera(&self) -> Option<Entity> {
+        self.camera
+    }
+}
+
+impl Default for PixelBackground {
+    fn default() -> Self {
+        Self::new(Color::BLACK)
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The camera of a `PixelBackground`.
+pub(crate) struct BackgroundCamera {
+    camera: Entity,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The sprite drawing the image of a `PixelBackground`.
+pub(crate) struct BackgroundSprite;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_background_system(
+    mut commands: Commands,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut cameras: Query<(Entity, &Camera, &mut PixelBackground), With<PixelZoom>>,
+    others: Query<&Camera, Without<BackgroundCamera>>,
+    mut backgrounds: Query<
+        (
+            Entity,
+            &BackgroundCamera,
+            &mut Camera,
+            &mut RenderLayers,
+            Option<&Children>,
+        ),
+        Without<PixelZoom>,
+    >,
+    mut sprites: Query<
+        (
+            &mut Sprite,
+            &mut Handle<Image>,
+            &mut Visibility,
+            &mut RenderLayers,
+        ),
+        (With<BackgroundSprite>, Without<BackgroundCamera>),
+    >,
+) {
+    for (entity, background, ..) in &backgrounds {
+        let removed = cameras
+            .get(background.camera)
+            .map_or(true, |(_, _, pixel)| pixel.camera != Some(entity));
+        if removed {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let primary_window = primary_window.iter().next();
+    for (entity, camera, mut background) in &mut cameras {
+        // One below the lowest order of the cameras sharing the target.
+        let target = camera.target.normalize(primary_window);
+        let order = others
+            .iter()
+            .filter(|other| other.target.normalize(primary_window) == target)
+            .map(|other| other.order)
+            .min()
+            .unwrap_or(camera.order)
+            - 1;
+        let layer = RenderLayers::layer(background.layer);
+
+        let Some(background_entity) = background.camera else {
+            let background_camera = commands
+                .spawn((
+                    Name::new("Background Camera"),
+                    HelperCamera,
+                    BackgroundCamera { camera: entity },
+                    NoPixelSnap,
+                    Camera2dBundle {
+                        camera: Camera {
+                            order,
+                            target: camera.target.clone(),
+                            clear_color: ClearColorConfig::Custom(background.color),
+                            ..Default::default()
+                        },
+                        projection: OrthographicProjection {
+                            scaling_mode: ScalingMode::WindowSize(1.0),
+                            ..Camera2dBundle::default().projection
+                        },
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .id();
+            commands
+                .spawn((
+                    Name::new("Background Image"),
+                    BackgroundSprite,
+                    SpriteBundle {
+                        visibility: Visibility::Hidden,
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .set_parent(background_camera);
+            background.camera = Some(background_camera);
+            continue;
+        };
+
+        let Ok((_, _, mut background_camera, mut layers, children)) =
+            backgrounds.get_mut(background_entity)
+        else {
+            continue;
+        };
+        if background_camera.order != order {
+            background_camera.order = order;
+        }
+        let same_color = match background_camera.clear_color {
+            ClearColorConfig::Custom(color) => color == background.color,
+            _ => false,
+        };
+        if !same_color {
+            background_camera.clear_color = ClearColorConfig::Custom(background.color);
+        }
+        if *layers != layer {
+            *layers = layer;
+        }
+
+        let size = camera.logical_target_size();
+        for child in children.into_iter().flatten() {
+            let Ok((mut sprite, mut image, mut visibility, mut layers)) = sprites.get_mut(*child)
+            else {
+                continue;
+            };
+            let wanted = match (&background.image, size) {
+                (Some(_), Some(_)) => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+            if *visibility != wanted {
+                *visibility = wanted;
+            }
+            if let Some(texture) = &background.image {
+                if *image != *texture {
+                    *image = texture.clone();
+                }
+            }
+            if sprite.custom_size != size {
+                sprite.custom_size = size;
+            }
+            if *layers != layer {
+                *layers = layer;
+            }
+        }
+    }
+}
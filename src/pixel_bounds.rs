@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy::render::camera::{Projection, ScalingMode};
+
+use crate::PixelZoom;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Keep the area visible through a `PixelZoom` camera inside the specified
+/// rectangle (in world units), e.g. the bounds of the level.
+///
+/// The camera translation is clamped in `PostUpdate`, after
+/// `PixelCameraFollow` and with the zoom of the current frame. On each axis
+/// where the bounds are smaller than the visible area, the camera is centered
+/// on the bounds instead. Rotations of the camera are ignored.
+pub struct PixelCameraBounds(pub Rect);
+
+/// The area visible through a camera, relative to its translation.
+fn visible_area(camera: &Camera, orthographic: &OrthographicProjection) -> Rect {
+    // The area of the projection is only updated in the next frame after a
+    // zoom change.
+    let size = match (orthographic.scaling_mode, camera.logical_viewport_size()) {
+        (ScalingMode::WindowSize(pixels_per_unit), Some(viewport_size))
+            if pixels_per_unit > 0.0 =>
+        {
+            viewport_size / pixels_per_unit * orthographic.scale
+        }
+        _ => orthographic.area.size(),
+    };
+    let origin = orthographic.viewport_origin;
+    Rect {
+        min: -size * origin,
+        max: size * (Vec2::ONE - origin),
+    }
+}
+
+/// Clamp the translation of a camera so that its visible area stays inside
+/// `bounds`.
+fn clamp_to_bounds(translation: Vec2, visible: Rect, bounds: Rect) -> Vec2 {
+    let min = bounds.min - visible.min;
+    let max = bounds.max - visible.max;
+    let centered = bounds.center() - visible.center();
+    Vec2::new(
+        if min.x <= max.x {
+            translation.x.clamp(min.x, max.x)
+        } else {
+            centered.x
+        },
+        if min.y <= max.y {
+            translation.y.clamp(min.y, max.y)
+        } else {
+            centered.y
+        },
+    )
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_camera_bounds_system(
+    mut cameras: Query<
+        (
+            &Camera,
+            &PixelCameraBounds,
+            &mut Transform,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+        ),
+        With<PixelZoom>,
+    >,
+) {
+    for (camera, bounds, mut transform, orthographic, projection) in &mut cameras {
+        let orthographic = match (orthographic, projection) {
+            (Some(orthographic), _) => orthographic,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic,
+            _ => continue,
+        };
+        let visible = visible_area(camera, orthographic);
+        let translation = clamp_to_bounds(transform.translation.truncate(), visible, bounds.0)
+            .extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{PixelViewport, PixelZoom};
+
+#[derive(Bundle)]
+/// The components of a pixel camera: a `Camera2dBundle`, with a `PixelZoom`
+/// and a `PixelViewport`.
+///
+/// This is the replacement of the deprecated `PixelCameraBundle`. Note that
+/// multisample anti-aliasing is a global setting in Bevy: use
+/// `PixelCameraPlugin::auto_msaa_off` (or insert `Msaa::Off`) to disable it,
+/// and `ImagePlugin::default_nearest()` for crisp sprites.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_camera::PixelCamera;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn(PixelCamera::fit(320, 180));
+/// }
+/// ```
+pub struct PixelCamera {
+    /// The components of the camera itself.
+    pub camera: Camera2dBundle,
+    /// The zoom mode of the camera.
+    pub zoom: PixelZoom,
+    /// The viewport of the camera.
+    pub viewport: PixelViewport,
+}
+
+impl PixelCamera {
+    /// A camera with the specified zoom mode and a default viewport.
+    pub fn new(zoom: PixelZoom) -> Self {
+        Self {
+            camera: Camera2dBundle::default(),
+            zoom,
+            viewport: PixelViewport::default(),
+        }
+    }
+
+    /// A camera using `PixelZoom::FitSize`, with a default viewport.
+    pub fn fit(width: i32, height: i32) -> Self {
+        Self::new(PixelZoom::FitSize { width, height })
+    }
+
+    /// Replace the viewport of the camera.
+    pub fn with_viewport(self, viewport: PixelViewport) -> Self {
+        Self { viewport, ..self }
+    }
+
+    /// Set the position of the camera.
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.camera.transform.translation = translation;
+        self
+    }
+}
+
+impl Default for PixelCamera {
+    fn default() -> Self {
+        Self::new(PixelZoom::Fixed(1))
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use bevy::render::camera::{Projection, ScalingMode};
+
+use crate::pixel_zoom::camera_target_size;
+use crate::{PixelTargetSize, PixelZoom};
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+/// The effective configuration of a `PixelZoom` camera, as computed by the
+/// plugin.
+///
+/// This component is added to every `PixelZoom` camera, and refreshed in
+/// `PostUpdate` after the zoom (and any `PixelZoomTransition`) has been
+/// applied. It is only modified when one of its values changes, so it can be
+/// used with `Changed<PixelCameraInfo>`. Modifying it has no effect.
+///
+/// For a `PixelCanvas` camera, it describes the rendering into the canvas
+/// image, not the upscaling to the window (see `PixelCameraQuery` for that).
+pub struct PixelCameraInfo {
+    /// The size of a virtual pixel, in logical screen pixels (horizontal and
+    /// vertical).
+    pub zoom: Vec2,
+    /// The number of virtual pixels visible in the viewport (may be
+    /// fractional with a `PixelZoom` that doesn't crop the viewport).
+    pub virtual_size: Vec2,
+    /// The viewport of the camera, in physical pixels of the render target.
+    pub viewport_rect: URect,
+    /// The size of the areas of the render target outside of the viewport,
+    /// in physical pixels.
+    pub bar_sizes: BarSizes,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The size of the bars around the viewport of a camera, in physical pixels.
+pub struct BarSizes {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl PixelCameraInfo {
+    /// Compute the info of a camera from its scaling mode and viewport.
+    fn compute(
+        camera: &Camera,
+        orthographic: &OrthographicProjection,
+        target_size: Option<&PixelTargetSize>,
+    ) -> Option<Self> {
+        let (logical_size, target_size) = camera_target_size(camera, target_size)?;
+        let scale_factor = target_size.x as f32 / logical_size.x;
+        let viewport_size = match &camera.viewport {
+            Some(viewport) => viewport.physical_size.as_vec2() / scale_factor,
+            None => logical_size,
+        };
+        let zoom = match orthographic.scaling_mode {
+            ScalingMode::WindowSize(zoom) => Vec2::splat(zoom),
+            ScalingMode::Fixed { width, height } => viewport_size / Vec2::new(width, height),
+            _ => return None,
+        };
+        if !(zoom.x > 0.0 && zoom.y > 0.0) {
+            return None;
+        }
+        let viewport_rect = match &camera.viewport {
+            Some(viewport) => URect::from_corners(
+                viewport.physical_position,
+                viewport.physical_position + viewport.physical_size,
+            ),
+            None => URect::from_corners(UVec2::ZERO, target_size),
+        };
+        let far = target_size.saturating_sub(viewport_rect.max);
+        Some(Self {
+            zoom,
+            virtual_size: viewport_size / zoom,
+            viewport_rect,
+            bar_sizes: BarSizes {
+                left: viewport_rect.min.x,
+                right: far.x,
+                top: viewport_rect.min.y,
+                bottom: far.y,
+            },
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_camera_info_system(
+    mut commands: Commands,
+    mut cameras: Query<
+        (
+            Entity,
+            &Camera,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+            Option<&mut PixelCameraInfo>,
+            Option<&PixelTargetSize>,
+        ),
+        With<PixelZoom>,
+    >,
+) {
+    for (entity, camera, orthographic, projection, info, target_size) in &mut cameras {
+        let orthographic = match (orthographic, projection) {
+            (Some(orthographic), _) => orthographic,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic,
+            _ => continue,
+        };
+        let Some(computed) = PixelCameraInfo::compute(camera, orthographic, target_size) else {
+            continue;
+        };
+        match info {
+            Some(mut info) => {
+                if *info != computed {
+                    *info = computed;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(computed);
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::window::PrimaryWindow;
+
+use crate::pixel_cursor::{
+    camera_window, virtual_pixel_scale, window_to_viewport, window_to_world,
+    world_to_virtual_pixel, CameraQuery,
+};
+use crate::PixelCanvas;
+
+#[derive(SystemParam)]
+/// A system parameter giving access to the `PixelZoom` cameras, with
+/// conversions between screen, world and virtual pixel coordinates.
+///
+/// Screen positions are logical positions in the window the camera is
+/// displayed in (as returned by `Window::cursor_position`). The conversions
+/// take into account the position of the viewport, the zoom, and the
+/// upscaling of a `PixelCanvas`. Screen positions outside of the viewport
+/// (e.g. over the letterbox bars) are never converted.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_camera::PixelCameraQuery;
+/// fn hover(windows: Query<&Window>, cameras: PixelCameraQuery) {
+///     let Some(camera) = cameras.single() else {
+///         return;
+///     };
+///     let cursor = windows.iter().find_map(|window| window.cursor_position());
+///     if let Some(world) = cursor.and_then(|cursor| camera.screen_to_world(cursor)) {
+///         info!("Hovering virtual pixel {:?}", camera.world_to_virtual_pixel(world));
+///     }
+/// }
+/// ```
+pub struct PixelCameraQuery<'w, 's> {
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    windows: Query<'w, 's, &'static Window>,
+    cameras: CameraQuery<'w, 's>,
+}
+
+impl<'w, 's> PixelCameraQuery<'w, 's> {
+    /// The `PixelZoom` camera `entity`.
+    pub fn get(&self, entity: Entity) -> Option<PixelCameraView<'_>> {
+        self.view(self.cameras.get(entity).ok()?)
+    }
+
+    /// The `PixelZoom` camera, if there is exactly one.
+    pub fn single(&self) -> Option<PixelCameraView<'_>> {
+        self.view(self.cameras.get_single().ok()?)
+    }
+
+    /// Iterate over all the `PixelZoom` cameras.
+    pub fn iter(&self) -> impl Iterator<Item = PixelCameraView<'_>> + '_ {
+        self.cameras.iter().filter_map(|item| self.view(item))
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn view<'a>(
+        &'a self,
+        (entity, camera, transform, canvas, orthographic, projection): (
+            Entity,
+            &'a Camera,
+            &'a GlobalTransform,
+            Option<&'a PixelCanvas>,
+            Option<&'a OrthographicProjection>,
+            Option<&'a Projection>,
+        ),
+    ) -> Option<PixelCameraView<'a>> {
+        let primary_window = self.primary_window.iter().next();
+        let window = camera_window(camera, canvas, primary_window)
+            .and_then(|window| Some((window, self.windows.get(window).ok()?)));
+        Some(PixelCameraView {
+            entity,
+            camera,
+            transform,
+            canvas,
+            orthographic,
+            projection,
+            window,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+/// A `PixelZoom` camera, as returned by `PixelCameraQuery`.
+pub struct PixelCameraView<'a> {
+    entity: Entity,
+    camera: &'a Camera,
+    transform: &'a GlobalTransform,
+    canvas: Option<&'a PixelCanvas>,
+    orthographic: Option<&'a OrthographicProjection>,
+    projection: Option<&'a Projection>,
+    window: Option<(Entity, &'a Window)>,
+}
+
+impl<'a> PixelCameraView<'a> {
+    /// The camera entity.
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The camera component.
+    pub fn camera(&self) -> &'a Camera {
+        self.camera
+    }
+
+    /// The window the camera is displayed in (for a `PixelCanvas`, the window
+    /// of the upscaled canvas).
+    pub fn window(&self) -> Option<Entity> {
+        self.window.map(|(entity, _)| entity)
+    }
+
+    /// The current zoom, in logical pixels per virtual pixel (horizontally
+    /// and vertically).
+    pub fn zoom(&self) -> Option<Vec2> {
+        if let Some(canvas) = self.canvas {
+            return canvas.zoom();
+        }
+        let orthographic = match (self.orthographic, self.projection) {
+            (Some(orthographic), _) => orthographic,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic,
+            _ => return None,
+        };
+        let viewport_size = self.camera.logical_viewport_size()?;
+        let area = orthographic.area.size();
+        (area.x > 0.0 && area.y > 0.0).then(|| viewport_size / area * orthographic.scale)
+    }
+
+    /// The logical area of the window where the camera is displayed.
+    pub fn viewport(&self) -> Option<Rect> {
+        match self.canvas {
+            Some(canvas) => canvas.display_rect(self.window?.1),
+            None => self.camera.logical_viewport_rect(),
+        }
+    }
+
+    /// Whether a screen position is inside the viewport, i.e. not over the
+    /// letterbox bars (or outside of the upscaled canvas).
+    pub fn is_inside_viewport(&self, position: Vec2) -> bool {
+        self.viewport()
+            .is_some_and(|viewport| viewport.contains(position))
+    }
+
+    /// Convert a screen position into a position relative to the viewport of
+    /// the camera (for a `PixelCanvas`, a position in its image), if it is
+    /// inside the viewport.
+    ///
+    /// This is the position expected by `Camera::viewport_to_world` and
+    /// `Camera::viewport_to_world_2d`. Picking backends (e.g. for
+    /// `bevy_mod_picking`) should use it to convert pointer locations, instead
+    /// of the raw window position: the latter ignores the letterbox offset of
+    /// a `PixelViewport`, and the upscaling of a canvas.
+    pub fn screen_to_viewport(&self, position: Vec2) -> Option<Vec2> {
+        let (_, window) = self.window?;
+        window_to_viewport(self.camera, self.canvas, window, position)
+    }
+
+    /// Convert a screen position into world coordinates, if it is inside the
+    /// viewport. Positions over the letterbox bars return `None`, instead of
+    /// being extrapolated outside of the visible area.
+    pub fn screen_to_world(&self, position: Vec2) -> Option<Vec2> {
+        let (_, window) = self.window?;
+        window_to_world(self.camera, self.transform, self.canvas, window, position)
+    }
+
+    /// Convert a world position into a screen position.
+    pub fn world_to_screen(&self, position: Vec2) -> Option<Vec2> {
+        let (_, window) = self.window?;
+        let viewport_position = self
+            .camera
+            .world_to_viewport(self.transform, position.extend(0.0))?;
+        match self.canvas {
+            Some(canvas) => canvas.image_to_window(viewport_position, window),
+            None => Some(viewport_position + self.camera.logical_viewport_rect()?.min),
+        }
+    }
+
+    /// The virtual pixel containing a world position.
+    pub fn world_to_virtual_pixel(&self, position: Vec2) -> Option<IVec2> {
+        world_to_virtual_pixel(position, self.orthographic, self.projection)
+    }
+
+    /// The area of the world visible through the camera (its bounding box, if
+    /// the camera is rotated).
+    pub fn visible_world_rect(&self) -> Option<Rect> {
+        let viewport = self.viewport()?;
+        let corners = [
+            viewport.min,
+            viewport.max,
+            Vec2::new(viewport.min.x, viewport.max.y),
+            Vec2::new(viewport.max.x, viewport.min.y),
+        ];
+        let mut visible: Option<Rect> = None;
+        for corner in corners {
+            let corner = self.screen_to_world(corner)?;
+            visible = Some(
+                visible.map_or(Rect::from_corners(corner, corner), |visible| {
+                    visible.union_point(corner)
+                }),
+            );
+        }
+        visible
+    }
+
+    /// The virtual pixels visible (even partially) through the camera. The
+    /// maximum corner is exclusive.
+    pub fn visible_virtual_rect(&self) -> Option<IRect> {
+        let visible = self.visible_world_rect()?;
+        let pixel_size = virtual_pixel_scale(self.orthographic, self.projection)?;
+        Some(IRect::from_corners(
+            (visible.min / pixel_size).floor().as_ivec2(),
+            (visible.max / pixel_size).ceil().as_ivec2(),
+        ))
+    }
+}
@@ -0,0 +1,647 @@
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::camera::{
+    ClearColorConfig, NormalizedRenderTarget, RenderTarget, ScalingMode, Viewport,
+};
+use bevy::render::texture::ImageSampler;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::{Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle};
+use bevy::transform::TransformSystem;
+use bevy::utils::{HashMap, HashSet};
+use bevy::window::{PrimaryWindow, WindowRef};
+
+use crate::pixel_default::HelperCamera;
+use crate::pixel_plugin::pixel_camera_enabled;
+use crate::pixel_upscale::{PIXEL_UPSCALE_BINDINGS_SHADER_HANDLE, PIXEL_UPSCALE_SHADER_HANDLE};
+use crate::pixel_zoom::{same_viewport, OrthographicQuery, ZoomSettings};
+use crate::render_target::{render_target_image, resize_render_target};
+use crate::{
+    math, NoPixelSnap, PixelCameraPlugin, PixelSafeArea, PixelUpscaleMaterial, PixelZoom,
+    PixelZoomChanged, PixelZoomOverride, SnapCameraToPixelGrid, UpscaleFilter,
+};
+
+/// Provides the offscreen canvas rendering method (see `PixelCanvas`).
+///
+/// This plugin also adds the `PixelCameraPlugin` if needed.
+pub struct PixelCanvasPlugin;
+
+impl Plugin for PixelCanvasPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PixelCameraPlugin>() {
+            app.add_plugins(PixelCameraPlugin::default());
+        }
+        load_internal_asset!(
+            app,
+            PIXEL_UPSCALE_BINDINGS_SHADER_HANDLE,
+            "pixel_upscale_bindings.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            PIXEL_UPSCALE_SHADER_HANDLE,
+            "pixel_upscale.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(Material2dPlugin::<PixelUpscaleMaterial>::default())
+            .add_systems(
+                PostUpdate,
+                crate::pixel_scanlines::pixel_scanlines_system.after(pixel_canvas_system),
+            );
+        #[cfg(feature = "crt")]
+        app.add_systems(
+            PostUpdate,
+            crate::pixel_crt::pixel_crt_system.after(pixel_canvas_system),
+        );
+        app.add_systems(
+            PostUpdate,
+            pixel_canvas_system
+                .before(crate::pixel_zoom::pixel_zoom_system)
+                .run_if(pixel_camera_enabled),
+        )
+        .add_systems(
+            PostUpdate,
+            pixel_canvas_scroll_system
+                .after(crate::pixel_snap::snap_camera_system)
+                .before(TransformSystem::TransformPropagate)
+                .run_if(pixel_camera_enabled),
+        );
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+/// Render a `PixelZoom` camera to a low resolution image, upscaled to the
+/// window.
+///
+/// This is the "offscreen texture" method: the camera renders the world at
+/// exactly one texel per virtual pixel, and the resulting image is displayed
+/// in the original window by an additional camera (on the render layer
+/// `layer`), scaled by the zoom computed from the `PixelZoom` mode. Sprites
+/// are always aligned on virtual pixels, whatever their position.
+///
+/// Several canvases can be displayed in the same window (e.g. a background,
+/// the gameplay and a user interface at twice the density), each with its own
+/// camera, `PixelZoom` and `layer`. They are composited in the order of their
+/// cameras: only the lowest one clears the window, and the canvases above it
+/// are cleared to transparent (unless their camera uses a custom clear color).
+///
+/// A `PixelHiResOverlay` on the camera is composited at the native resolution
+/// of the window, above the canvas (e.g. for a `bevy_ui` interface).
+///
+/// When the component is removed, the display of the canvas is despawned, and
+/// the camera renders to the window again.
+///
+/// Requires the `PixelCanvasPlugin`.
+pub struct PixelCanvas {
+    /// The render layer used to display the canvas.
+    pub layer: u8,
+    /// The filter used to upscale the canvas to the window.
+    pub filter: UpscaleFilter,
+    /// Scroll smoothly, while keeping the sprites aligned on virtual pixels.
+    ///
+    /// The camera is snapped to whole virtual pixels (a
+    /// `SnapCameraToPixelGrid` is added if needed), and the upscaled canvas
+    /// is offset by the remainder, in physical pixels. The canvas is rendered
+    /// with one virtual pixel of margin on each side, so that the offset
+    /// never uncovers its edges.
+    pub smooth_scroll: bool,
+    state: Option<CanvasState>,
+}
+
+#[derive(Debug, Clone)]
+struct CanvasState {
+    window: Entity,
+    image: Handle<Image>,
+    material: Handle<PixelUpscaleMaterial>,
+    display_camera: Entity,
+    display: Entity,
+    size: UVec2,
+    zoom: Option<Vec2>,
+    /// Offset of the upscaled canvas, in logical pixels (see `smooth_scroll`).
+    scroll: Vec2,
+}
+
+/// The margin of the canvas when scrolling smoothly, in virtual pixels.
+const SCROLL_MARGIN: u32 = 2;
+
+impl PixelCanvas {
+    /// Render to a canvas, displayed on the specified render layer.
+    pub fn new(layer: u8) -> Self {
+        Self {
+            layer,
+            filter: UpscaleFilter::default(),
+            smooth_scroll: false,
+            state: None,
+        }
+    }
+
+    /// Use the specified filter to upscale the canvas.
+    pub fn with_filter(self, filter: UpscaleFilter) -> Self {
+        Self { filter, ..self }
+    }
+
+    /// Enable smooth scrolling (see `smooth_scroll`).
+    pub fn with_smooth_scroll(self) -> Self {
+        Self {
+            smooth_scroll: true,
+            ..self
+        }
+    }
+
+    /// The material used to display the canvas, once it has been created.
+    pub(crate) fn material(&self) -> Option<&Handle<PixelUpscaleMaterial>> {
+        self.state.as_ref().map(|state| &state.material)
+    }
+
+    /// The window the canvas is displayed in, the size of the canvas (without
+    /// the smooth scrolling margin) and its zoom, once they are known.
+    #[cfg(feature = "recording")]
+    pub(crate) fn display(&self) -> Option<(Entity, UVec2, Vec2)> {
+        let state = self.state.as_ref()?;
+        Some((state.window, state.size, state.zoom?))
+    }
+
+    /// The window the canvas is displayed in, once it has been created.
+    pub(crate) fn window(&self) -> Option<Entity> {
+        self.state.as_ref().map(|state| state.window)
+    }
+
+    /// The logical area of the window covered by the displayed canvas, once
+    /// its zoom is known.
+    pub(crate) fn display_rect(&self, window: &Window) -> Option<Rect> {
+        let state = self.state.as_ref()?;
+        let zoom = state.zoom?;
+        let center = Vec2::new(window.width(), window.height()) / 2.0;
+        Some(Rect::from_center_size(center, state.size.as_vec2() * zoom))
+    }
+
+    /// The size of the image of the canvas, and the offset of its center in
+    /// the window, in logical pixels with the Y axis pointing down.
+    fn image_layout(&self) -> Option<(Vec2, Vec2)> {
+        let state = self.state.as_ref()?;
+        let margin = if self.smooth_scroll { SCROLL_MARGIN } else { 0 };
+        let image_size = (state.size + UVec2::splat(margin)).as_vec2();
+        // The display is offset in world units, with the Y axis pointing up.
+        Some((image_size, Vec2::new(state.scroll.x, -state.scroll.y)))
+    }
+
+    /// Convert a logical position in the window into a logical position in
+    /// the image of the canvas, if it is over the displayed canvas.
+    pub(crate) fn window_to_image(&self, position: Vec2, window: &Window) -> Option<Vec2> {
+        let display = self.display_rect(window)?;
+        if !display.contains(position) {
+            return None;
+        }
+        let zoom = self.state.as_ref()?.zoom?;
+        let (image_size, scroll) = self.image_layout()?;
+        Some(image_size / 2.0 + (position - display.center() - scroll) / zoom)
+    }
+
+    /// Convert a logical position in the image of the canvas into a logical
+    /// position in the window.
+    pub(crate) fn image_to_window(&self, position: Vec2, window: &Window) -> Option<Vec2> {
+        let display = self.display_rect(window)?;
+        let zoom = self.state.as_ref()?.zoom?;
+        let (image_size, scroll) = self.image_layout()?;
+        Some(display.center() + scroll + (position - image_size / 2.0) * zoom)
+    }
+
+    /// The zoom of the canvas (logical pixels per virtual pixel), once it is
+    /// known.
+    pub(crate) fn zoom(&self) -> Option<Vec2> {
+        self.state.as_ref()?.zoom
+    }
+
+    /// The image the camera renders to, once it has been created.
+    pub fn image(&self) -> Option<&Handle<Image>> {
+        self.state.as_ref().map(|state| &state.image)
+    }
+}
+
+impl Default for PixelCanvas {
+    fn default() -> Self {
+        Self::new(RenderLayers::TOTAL_LAYERS as u8 - 3)
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+/// The camera displaying the image of a `PixelCanvas` in its window.
+pub(crate) struct CanvasDisplayCamera {
+    camera: Entity,
+    image: Handle<Image>,
+}
+
+#[allow(
+    clippy::type_complexity,
+    clippy::too_many_arguments,
+    clippy::unnecessary_map_or
+)]
+pub(crate) fn pixel_canvas_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<PixelUpscaleMaterial>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    zoom_override: Res<PixelZoomOverride>,
+    mut zoom_changed_events: EventWriter<PixelZoomChanged>,
+    mut cameras: Query<(
+        Entity,
+        &mut Camera,
+        &PixelZoom,
+        &mut PixelCanvas,
+        Has<SnapCameraToPixelGrid>,
+        OrthographicQuery,
+        ZoomSettings,
+    )>,
+    mut display_cameras: Query<&mut Camera, Without<PixelCanvas>>,
+    display_markers: Query<(Entity, &CanvasDisplayCamera)>,
+    mut displays: Query<&mut Transform>,
+    // The lowest camera order of the canvases displayed in each window, and
+    // the layers already in use (kept between frames to reuse the memory).
+    mut bottom_orders: Local<HashMap<Entity, isize>>,
+    mut used_layers: Local<HashSet<(Entity, u8)>>,
+) {
+    let primary_window = primary_window.iter().next();
+
+    // Despawn the displays of the canvases that have been removed (or whose
+    // camera has been despawned), and render their camera to the window
+    // again.
+    for (entity, display) in &display_markers {
+        let displayed = cameras
+            .get(display.camera)
+            .is_ok_and(|(_, _, _, canvas, ..)| {
+                canvas
+                    .state
+                    .as_ref()
+                    .is_some_and(|state| state.display_camera == entity)
+            });
+        if displayed {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        let Ok(window) = display_cameras
+            .get(entity)
+            .map(|camera| camera.target.clone())
+        else {
+            continue;
+        };
+        if let Ok(mut camera) = display_cameras.get_mut(display.camera) {
+            if matches!(&camera.target, RenderTarget::Image(image) if *image == display.image) {
+                camera.target = window;
+            }
+        }
+    }
+
+    bottom_orders.clear();
+    used_layers.clear();
+    for (_, camera, _, canvas, ..) in &cameras {
+        if let Some(state) = &canvas.state {
+            bottom_orders
+                .entry(state.window)
+                .and_modify(|order: &mut isize| *order = (*order).min(camera.order))
+                .or_insert(camera.order);
+            used_layers.insert((state.window, canvas.layer));
+        }
+    }
+
+    for (entity, mut camera, pixel_zoom, mut canvas, snapped, mut projection, settings) in
+        &mut cameras
+    {
+        if canvas.state.is_none() {
+            let Some(NormalizedRenderTarget::Window(window_ref)) =
+                camera.target.normalize(primary_window)
+            else {
+                continue;
+            };
+            if !used_layers.insert((window_ref.entity(), canvas.layer)) {
+                warn!(
+                    "Several canvases are displayed on layer {} of the same window",
+                    canvas.layer
+                );
+            }
+            // The upscale shader needs linear sampling, even for the nearest
+            // filter (it samples texel centers).
+            let image = images.add(render_target_image(UVec2::ONE, ImageSampler::linear()));
+            let material = materials.add(PixelUpscaleMaterial::new(image.clone()));
+            let layer = RenderLayers::layer(canvas.layer);
+            let display_camera = commands
+                .spawn((
+                    Name::new("Canvas Camera"),
+                    HelperCamera,
+                    CanvasDisplayCamera {
+                        camera: entity,
+                        image: image.clone(),
+                    },
+                    Camera2dBundle {
+                        camera: Camera {
+                            order: camera.order + 1,
+                            target: RenderTarget::Window(WindowRef::Entity(window_ref.entity())),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .id();
+            let display = commands
+                .spawn((
+                    Name::new("Canvas Image"),
+                    NoPixelSnap,
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(Rectangle::new(1.0, 1.0))),
+                        material: material.clone(),
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .set_parent(display_camera)
+                .id();
+            camera.target = RenderTarget::Image(image.clone());
+            canvas.state = Some(CanvasState {
+                window: window_ref.entity(),
+                image,
+                material,
+                display_camera,
+                display,
+                size: UVec2::ONE,
+                zoom: None,
+                scroll: Vec2::ZERO,
+            });
+        }
+
+        // The canvas is always rendered at one texel per virtual pixel.
+        projection.set_scaling_mode(ScalingMode::WindowSize(1.0));
+
+        if canvas.smooth_scroll && !snapped {
+            commands
+                .entity(entity)
+                .insert(SnapCameraToPixelGrid::default());
+        }
+
+        let is_bottom = canvas.state.as_ref().map_or(true, |state| {
+            bottom_orders
+                .get(&state.window)
+                .map_or(true, |&order| camera.order <= order)
+        });
+        if !is_bottom && matches!(camera.clear_color, ClearColorConfig::Default) {
+            camera.clear_color = ClearColorConfig::Custom(Color::NONE);
+        }
+
+        let filter = canvas.filter.clone();
+        let smooth_scroll = canvas.smooth_scroll;
+        let Some(state) = &mut canvas.state else {
+            continue;
+        };
+        let Ok(window) = windows.get(state.window) else {
+            continue;
+        };
+        let logical_size = Vec2::new(window.width(), window.height());
+        let physical_size = UVec2::new(window.physical_width(), window.physical_height());
+        if logical_size.x <= 0.0 || logical_size.y <= 0.0 {
+            continue;
+        }
+
+        let previous_zoom = state.zoom;
+        let (canvas_size, display_size, zoom) = match pixel_zoom {
+            PixelZoom::Stretch { width, height } => {
+                let canvas_size = IVec2::new(*width, *height).max(IVec2::ONE).as_uvec2();
+                let zoom = logical_size / canvas_size.as_vec2();
+                (canvas_size, logical_size, zoom)
+            }
+            _ => {
+                let zoom = settings.camera_zoom(
+                    pixel_zoom,
+                    None,
+                    &PixelSafeArea::default(),
+                    &zoom_override,
+                    logical_size,
+                    physical_size,
+                    previous_zoom.map(|zoom| zoom.y),
+                );
+                // Dimensions not constrained by the mode fill the window.
+                let (width, height) = math::target_dimensions(pixel_zoom, logical_size, zoom);
+                let canvas_size = UVec2::new(
+                    width.map_or((logical_size.x / zoom.x).ceil() as u32, |width| {
+                        width as u32
+                    }),
+                    height.map_or((logical_size.y / zoom.y).ceil() as u32, |height| {
+                        height as u32
+                    }),
+                )
+                .max(UVec2::ONE);
+                (canvas_size, canvas_size.as_vec2() * zoom, zoom)
+            }
+        };
+        // The margin uncovered by the sub-pixel offset.
+        let margin = if smooth_scroll { SCROLL_MARGIN } else { 0 };
+        let rendered_size = canvas_size + UVec2::splat(margin);
+        resize_render_target(&mut images, &state.image, rendered_size);
+
+        if let Ok(mut display_camera) = display_cameras.get_mut(state.display_camera) {
+            let clear_color = if is_bottom {
+                ClearColorConfig::Default
+            } else {
+                ClearColorConfig::None
+            };
+            if !matches!(
+                (&display_camera.clear_color, &clear_color),
+                (ClearColorConfig::Default, ClearColorConfig::Default)
+                    | (ClearColorConfig::None, ClearColorConfig::None)
+            ) {
+                display_camera.clear_color = clear_color;
+            }
+
+            // Clip the margin to the nominal size of the canvas.
+            let clip_size = (display_size * window.scale_factor())
+                .round()
+                .as_uvec2()
+                .min(physical_size);
+            let viewport = (smooth_scroll && clip_size != physical_size).then(|| Viewport {
+                physical_position: (physical_size - clip_size) / 2,
+                physical_size: clip_size.max(UVec2::ONE),
+                ..Default::default()
+            });
+            let unchanged = match (&display_camera.viewport, &viewport) {
+                (Some(current), Some(viewport)) => same_viewport(current, viewport),
+                (None, None) => true,
+                _ => false,
+            };
+            if !unchanged {
+                display_camera.viewport = viewport;
+            }
+        }
+
+        if let Ok(mut transform) = displays.get_mut(state.display) {
+            let scale = (display_size + margin as f32 * zoom).extend(1.0);
+            if transform.scale != scale {
+                transform.scale = scale;
+            }
+        }
+
+        PixelUpscaleMaterial::update(&mut materials, &state.material, |material| {
+            material.texture_size = rendered_size.as_vec2();
+            material.scale = display_size / canvas_size.as_vec2() * window.scale_factor();
+            material.set_filter(&filter);
+        });
+
+        state.size = canvas_size;
+        if previous_zoom != Some(zoom) {
+            state.zoom = Some(zoom);
+            zoom_changed_events.send(PixelZoomChanged::new(
+                entity,
+                previous_zoom.map(|zoom| zoom.y),
+                zoom.y,
+                &None,
+            ));
+        }
+    }
+}
+
+/// Offset the upscaled canvas by the sub-pixel remainder of the camera
+/// position (see `PixelCanvas::smooth_scroll`).
+pub(crate) fn pixel_canvas_scroll_system(
+    mut cameras: Query<(&mut PixelCanvas, Option<&SnapCameraToPixelGrid>)>,
+    windows: Query<&Window>,
+    mut displays: Query<&mut Transform, Without<PixelCanvas>>,
+) {
+    for (mut canvas, snapping) in &mut cameras {
+        let Some(state) = &canvas.state else {
+            continue;
+        };
+        let Ok(mut transform) = displays.get_mut(state.display) else {
+            continue;
+        };
+        let scroll = state.scroll;
+        let offset = match (snapping, state.zoom) {
+            (Some(snapping), Some(zoom)) if canvas.smooth_scroll => {
+                let scale_factor = windows.get(state.window).map_or(1.0, |w| w.scale_factor());
+                // Moving the camera moves the image in the opposite direction.
+                (-snapping.offset * zoom * scale_factor).round() / scale_factor
+            }
+            _ => Vec2::ZERO,
+        };
+        let translation = offset.extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+        if scroll != offset {
+            if let Some(state) = &mut canvas.state {
+                state.scroll = offset;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spawn_headless_window, PixelCameraHeadlessPlugin, PixelCanvasPlugin};
+
+    fn app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            PixelCameraHeadlessPlugin,
+        ))
+        .init_asset::<Shader>()
+        .init_asset::<Mesh>()
+        .add_plugins(PixelCanvasPlugin);
+        let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+        let camera = app
+            .world
+            .spawn((
+                Camera2dBundle::default(),
+                PixelZoom::FitSize {
+                    width: 320,
+                    height: 180,
+                },
+                PixelCanvas::default(),
+            ))
+            .id();
+        app.update();
+        (app, window, camera)
+    }
+
+    fn displays(app: &mut App) -> usize {
+        app.world
+            .query::<&CanvasDisplayCamera>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn canvas_is_displayed_in_the_center_of_the_window() {
+        let (app, window, camera) = app();
+        let canvas = app.world.get::<PixelCanvas>(camera).unwrap();
+        let window = app.world.get::<Window>(window).unwrap();
+        assert_eq!(canvas.zoom(), Some(Vec2::splat(2.0)));
+        assert_eq!(
+            canvas.display_rect(window),
+            Some(Rect::new(80.0, 120.0, 720.0, 480.0))
+        );
+        assert_eq!(
+            canvas.window_to_image(Vec2::new(81.0, 121.0), window),
+            Some(Vec2::new(0.5, 0.5))
+        );
+        assert_eq!(canvas.window_to_image(Vec2::new(40.0, 60.0), window), None);
+    }
+
+    #[test]
+    fn hires_overlay_is_composited_over_the_canvas() {
+        let (mut app, window, camera) = app();
+        app.world
+            .entity_mut(camera)
+            .insert(crate::PixelHiResOverlay::default());
+        app.update();
+
+        let overlay = app.world.get::<crate::PixelHiResOverlay>(camera).unwrap();
+        let overlay = app.world.get::<Camera>(overlay.camera().unwrap()).unwrap();
+        assert!(matches!(
+            overlay.target,
+            RenderTarget::Window(WindowRef::Entity(entity)) if entity == window
+        ));
+        let viewport = overlay.viewport.as_ref().unwrap();
+        assert_eq!(viewport.physical_position, UVec2::new(80, 120));
+        assert_eq!(viewport.physical_size, UVec2::new(640, 360));
+
+        let display = app.world.get::<PixelCanvas>(camera).unwrap();
+        let display = display.state.as_ref().unwrap().display_camera;
+        assert!(overlay.order > app.world.get::<Camera>(display).unwrap().order);
+    }
+
+    #[test]
+    fn display_is_despawned_with_the_canvas() {
+        let (mut app, window, camera) = app();
+        assert_eq!(displays(&mut app), 1);
+        assert!(matches!(
+            app.world.get::<Camera>(camera).unwrap().target,
+            RenderTarget::Image(_)
+        ));
+
+        app.world.entity_mut(camera).remove::<PixelCanvas>();
+        app.update();
+        assert_eq!(displays(&mut app), 0);
+        assert_eq!(
+            app.world.query::<&Mesh2dHandle>().iter(&app.world).count(),
+            0
+        );
+        let target = &app.world.get::<Camera>(camera).unwrap().target;
+        assert!(matches!(
+            target,
+            RenderTarget::Window(WindowRef::Entity(entity)) if *entity == window
+        ));
+    }
+
+    #[test]
+    fn display_is_despawned_with_the_camera() {
+        let (mut app, _, camera) = app();
+        app.world.despawn(camera);
+        app.update();
+        assert_eq!(displays(&mut app), 0);
+        assert_eq!(
+            app.world.query::<&Mesh2dHandle>().iter(&app.world).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::Anchor;
+
+use crate::NoPixelSnap;
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Animate horizontal bars over the top and bottom of a `PixelZoom` camera,
+/// e.g. for cutscenes.
+///
+/// Set `active` to slide the bars in, and reset it to slide them out. The
+/// bars cover whole rows of virtual pixels, at the edges of the area seen by
+/// the camera (i.e. inside the viewport of a `PixelViewport`, or the whole
+/// window without it). They are spawned as children of the camera, on the
+/// same render layers, just in front of the near plane.
+pub struct CinematicBars {
+    /// The height of each bar when fully shown, in virtual pixels.
+    pub height: u32,
+    /// The duration of the animation, in seconds.
+    pub duration: f32,
+    /// The color of the bars.
+    pub color: Color,
+    /// Whether the bars are shown.
+    pub active: bool,
+    progress: f32,
+}
+
+impl CinematicBars {
+    /// Inactive bars of `height` virtual pixels, animated in `duration`
+    /// seconds.
+    pub fn new(height: u32, duration: f32) -> Self {
+        Self {
+            height,
+            duration,
+            color: Color::BLACK,
+            active: false,
+            progress: 0.0,
+        }
+    }
+
+    /// The progress of the animation, from 0 (hidden) to 1 (fully shown).
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+impl Default for CinematicBars {
+    fn default() -> Self {
+        Self::new(24, 0.5)
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// One of the two bars of a `CinematicBars`.
+pub(crate) enum CinematicBar {
+    Top,
+    Bottom,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn spawn_cinematic_bars(
+    mut commands: Commands,
+    cameras: Query<(Entity, &CinematicBars, Option<&RenderLayers>), Added<CinematicBars>>,
+) {
+    for (entity, bars, layers) in &cameras {
+        for bar in [CinematicBar::Top, CinematicBar::Bottom] {
+            let anchor = match bar {
+                CinematicBar::Top => Anchor::TopLeft,
+                CinematicBar::Bottom => Anchor::BottomLeft,
+            };
+            let mut bar = commands.spawn((
+                Name::new("Cinematic Bar"),
+                bar,
+                NoPixelSnap,
+                SpriteBundle {
+                    sprite: Sprite {
+                        anchor,
+                        color: bars.color,
+                        custom_size: Some(Vec2::ZERO),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+            if let Some(layers) = layers {
+                bar.insert(*layers);
+            }
+            bar.set_parent(entity);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_cinematic_bars(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(
+        &mut CinematicBars,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+    mut bars: Query<(
+        Entity,
+        &CinematicBar,
+        Option<&Parent>,
+        &mut Sprite,
+        &mut Transform,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    for (mut settings, ..) in &mut cameras {
+        let target = if settings.active { 1.0 } else { 0.0 };
+        if settings.progress != target {
+            let step = if settings.duration > 0.0 {
+                dt / settings.duration
+            } else {
+                1.0
+            };
+            settings.progress = if settings.progress < target {
+                (settings.progress + step).min(target)
+            } else {
+                (settings.progress - step).max(target)
+            };
+        }
+    }
+
+    for (entity, bar, parent, mut sprite, mut transform) in &mut bars {
+        let Some((settings, orthographic, projection)) =
+            parent.and_then(|parent| cameras.get(parent.get()).ok())
+        else {
+            // The camera is gone, or no longer has `CinematicBars`.
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let projection = match (orthographic, projection) {
+            (Some(orthographic), _) => orthographic,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic,
+            _ => continue,
+        };
+        let area = projection.area;
+        let pixel_size = projection.scale;
+        if area.is_empty() || pixel_size <= 0.0 {
+            continue;
+        }
+
+        if sprite.color != settings.color {
+            sprite.color = settings.color;
+        }
+
+        // Ease in and out, by whole rows of virtual pixels.
+        let t = settings.progress;
+        let rows = (settings.height as f32 * t * t * (3.0 - 2.0 * t)).round();
+        let (edge, height) = match bar {
+            CinematicBar::Top => {
+                let inner = ((area.max.y / pixel_size).ceil() - rows) * pixel_size;
+                (area.max.y, area.max.y - inner)
+            }
+            CinematicBar::Bottom => {
+                let inner = ((area.min.y / pixel_size).floor() + rows) * pixel_size;
+                (area.min.y, inner - area.min.y)
+            }
+        };
+        let size = Vec2::new(area.width(), height.max(0.0));
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+        let translation = Vec3::new(area.min.x, edge, -projection.near - 0.2);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use bevy::{
+    ecs::system::{Command, EntityCommands, RunSystemOnce, SystemState},
+    prelude::*,
+};
+
+use crate::pixel_minimap::{minimap_camera, minimap_image};
+use crate::pixel_split_screen::split_screen_zoom_system;
+use crate::pixel_zoom::ZoomUpdate;
+use crate::{PixelCameraFollow, PixelMinimap, PixelSplitScreen, SplitScreenLayout};
+use crate::{
+    PixelLetterboxColor, PixelViewport, PixelZoom, PixelZoomOverride, PixelZoomPunch,
+    SnapCameraToPixelGrid, ViewportAlignment,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// The configuration of a pixel camera, spawned with
+/// `PixelCameraCommands::spawn_pixel_camera`.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_camera::{PixelCameraCommands, PixelCameraConfig};
+/// fn setup(mut commands: Commands) {
+///     commands.spawn_pixel_camera(PixelCameraConfig::fit(320, 180).with_viewport().centered());
+/// }
+/// ```
+pub struct PixelCameraConfig {
+    /// The zoom mode of the camera.
+    pub zoom: PixelZoom,
+    /// The viewport of the camera, if any.
+    pub viewport: Option<PixelViewport>,
+    /// The color of the letterbox bars, if any (only visible with a viewport).
+    pub letterbox_color: Option<Color>,
+    /// Whether to add `SnapCameraToPixelGrid`.
+    pub snap_to_grid: bool,
+    /// The entity followed by the camera, if any (see `PixelCameraFollow`).
+    pub follow: Option<Entity>,
+    /// The render order of the camera.
+    pub order: isize,
+}
+
+impl PixelCameraConfig {
+    /// A camera with the specified zoom mode, and no viewport.
+    pub fn new(zoom: PixelZoom) -> Self {
+        Self {
+            zoom,
+            viewport: None,
+            letterbox_color: None,
+            snap_to_grid: false,
+            follow: None,
+            order: 0,
+        }
+    }
+
+    /// A camera using `PixelZoom::FitSize`.
+    pub fn fit(width: i32, height: i32) -> Self {
+        Self::new(PixelZoom::FitSize { width, height })
+    }
+
+    /// A camera using `PixelZoom::Fixed`.
+    pub fn fixed(zoom: i32) -> Self {
+        Self::new(PixelZoom::Fixed(zoom))
+    }
+
+    /// Add a default `PixelViewport`, unless the configuration already has
+    /// one.
+    pub fn with_viewport(mut self) -> Self {
+        self.viewport.get_or_insert_with(PixelViewport::default);
+        self
+    }
+
+    /// Align the viewport with `alignment` (adding a viewport if needed).
+    pub fn aligned(mut self, alignment: ViewportAlignment) -> Self {
+        self.viewport
+            .get_or_insert_with(PixelViewport::default)
+            .alignment = alignment;
+        self
+    }
+
+    /// Center the viewport in the render target (adding a viewport if
+    /// needed).
+    pub fn centered(self) -> Self {
+        self.aligned(ViewportAlignment::Center)
+    }
+
+    /// Fill the area outside of the viewport with `color` (adding a viewport
+    /// if needed).
+    pub fn with_letterbox_color(mut self, color: Color) -> Self {
+        self.letterbox_color = Some(color);
+        self.with_viewport()
+    }
+
+    /// Snap the translation of the camera to the virtual pixel grid.
+    pub fn snapped(mut self) -> Self {
+        self.snap_to_grid = true;
+        self
+    }
+
+    /// Follow `target` with a `PixelCameraFollow`.
+    pub fn following(mut self, target: Entity) -> Self {
+        self.follow = Some(target);
+        self
+    }
+
+    /// Set the render order of the camera.
+    pub fn with_order(mut self, order: isize) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+impl From<PixelZoom> for PixelCameraConfig {
+    fn from(zoom: PixelZoom) -> Self {
+        Self::new(zoom)
+    }
+}
+
+/// Extension trait for `Commands`, to spawn a pixel camera or reconfigure it
+/// at runtime.
+///
+/// Each of these operations updates all the affected components of the camera
+/// (zoom mode, scaling mode and viewport) when the commands are applied, so the
+/// change is visible in the same frame, without waiting for a window resize
+/// (as long as the commands are issued before `PostUpdate`, where Bevy updates
+/// the camera projections).
+pub trait PixelCameraCommands {
+    /// Spawn a `Camera2dBundle` with all the components of `config`.
+    fn spawn_pixel_camera(&mut self, config: impl Into<PixelCameraConfig>) -> EntityCommands<'_>;
+
+    /// Spawn two cameras sharing the primary window, e.g. for two local
+    /// players, and return them.
+    ///
+    /// Each camera is spawned as with `spawn_pixel_camera`, with its viewport
+    /// restricted to its half of the window (according to `layout`), and a
+    /// `PixelSplitScreen` so that both cameras use the same zoom. The second
+    /// camera is rendered after the first one, and after its
+    /// `PixelLetterboxColor` and `PixelHiResOverlay` cameras, if any.
+    fn spawn_split_screen(
+        &mut self,
+        layout: SplitScreenLayout,
+        configs: [PixelCameraConfig; 2],
+    ) -> [Entity; 2];
+
+    /// Spawn a camera rendering `minimap` to a new image (sized to fit the
+    /// region, with nearest sampling), and return the camera and the image.
+    fn spawn_minimap(
+        &mut self,
+        minimap: PixelMinimap,
+        images: &mut Assets<Image>,
+    ) -> (Entity, Handle<Image>);
+
+    /// Change the target resolution of the camera, keeping its current
+    /// `PixelZoom` mode.
+    ///
+    /// `FitWidth` only uses `width`, `FitHeight` only uses `height`, and
+    /// `FitLargerDim` uses the larger of the two. A camera with a `Fixed` or
+    /// `FitBest` zoom is switched to `FitSize`.
+    fn set_target_resolution(&mut self, camera: Entity, width: i32, height: i32);
+
+    /// Replace the `PixelZoom` mode of the camera.
+    fn set_zoom_mode(&mut self, camera: Entity, pixel_zoom: PixelZoom);
+
+    /// Add or remove the `PixelViewport` of the camera. When disabled, the
+    /// viewport of the camera is reset to the whole render target.
+    fn enable_viewport(&mut self, camera: Entity, enabled: bool);
+
+    /// Set the color of the area outside of the viewport, by adding or
+    /// updating the `PixelLetterboxColor` of the camera.
+    fn set_letterbox_color(&mut self, camera: Entity, color: Color);
+
+    /// Start a `PixelZoomPunch` on the camera, zooming in by `strength` and
+    /// settling back on the exact zoom in `duration` seconds. A punch already
+    /// in progress is restarted.
+    fn zoom_punch(&mut self, camera: Entity, strength: f32, duration: f32);
+}
+
+impl PixelCameraCommands for Commands<'_, '_> {
+    fn spawn_pixel_camera(&mut self, config: impl Into<PixelCameraConfig>) -> EntityCommands<'_> {
+        let config = config.into();
+        let mut camera = self.spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: config.order,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            config.zoom,
+        ));
+        if let Some(viewport) = config.viewport {
+            camera.insert(viewport);
+        }
+        if let Some(color) = config.letterbox_color {
+            camera.insert(PixelLetterboxColor::new(color));
+        }
+        if config.snap_to_grid {
+            camera.insert(SnapCameraToPixelGrid::default());
+        }
+        if let Some(target) = config.follow {
+            camera.insert(PixelCameraFollow::new(target));
+        }
+        camera
+    }
+
+    fn spawn_split_screen(
+        &mut self,
+        layout: SplitScreenLayout,
+        configs: [PixelCameraConfig; 2],
+    ) -> [Entity; 2] {
+        let mut cameras = [Entity::PLACEHOLDER; 2];
+        for (i, (config, region)) in configs.into_iter().zip(layout.regions()).enumerate() {
+            // Leave room for the letterbox and overlay cameras of the first
+            // camera.
+            let order = config.order + 3 * i as isize;
+            let mut config = config.with_viewport().with_order(order);
+            if let Some(viewport) = &mut config.viewport {
+                viewport.region = region;
+            }
+            cameras[i] = self
+                .spawn_pixel_camera(config)
+                .insert(PixelSplitScreen)
+                .id();
+        }
+        cameras
+    }
+
+    fn spawn_minimap(
+        &mut self,
+        minimap: PixelMinimap,
+        images: &mut Assets<Image>,
+    ) -> (Entity, Handle<Image>) {
+        let image = images.add(minimap_image(&minimap));
+        let camera = self.spawn(minimap_camera(&minimap, image.clone())).id();
+        (camera, image)
+    }
+
+    fn set_target_resolution(&mut self, camera: Entity, width: i32, height: i32) {
+        self.add(ReconfigurePixelCamera {
+            camera,
+            change: Change::TargetResolution { width, height },
+        });
+    }
+
+    fn set_zoom_mode(&mut self, camera: Entity, pixel_zoom: PixelZoom) {
+        self.add(ReconfigurePixelCamera {
+            camera,
+            change: Change::ZoomMode(pixel_zoom),
+        });
+    }
+
+    fn enable_viewport(&mut self, camera: Entity, enabled: bool) {
+        self.add(ReconfigurePixelCamera {
+            camera,
+            change: Change::Viewport(enabled),
+        });
+    }
+
+    fn set_letterbox_color(&mut self, camera: Entity, color: Color) {
+        self.add(ReconfigurePixelCamera {
+            camera,
+            change: Change::LetterboxColor(color),
+        });
+    }
+
+    fn zoom_punch(&mut self, camera: Entity, strength: f32, duration: f32) {
+        self.add(ReconfigurePixelCamera {
+            camera,
+            change: Change::ZoomPunch { strength, duration },
+        });
+    }
+}
+
+enum Change {
+    TargetResolution { width: i32, height: i32 },
+    ZoomMode(PixelZoom),
+    Viewport(bool),
+    LetterboxColor(Color),
+    ZoomPunch { strength: f32, duration: f32 },
+}
+
+struct ReconfigurePixelCamera {
+    camera: Entity,
+    change: Change,
+}
+
+impl Command for ReconfigurePixelCamera {
+    fn apply(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.camera) else {
+            warn!(
+                "cannot reconfigure pixel camera {:?}: no such entity",
+                self.camera
+            );
+            return;
+        };
+
+        match self.change {
+            Change::TargetResolution { width, height } => {
+                let pixel_zoom = match entity.get::<PixelZoom>() {
+                    Some(PixelZoom::FitWidth(_)) => PixelZoom::FitWidth(width),
+                    Some(PixelZoom::FitHeight(_)) => PixelZoom::FitHeight(height),
+                    Some(PixelZoom::FitLargerDim(_)) => {
+                        PixelZoom::FitLargerDim(i32::max(width, height))
+                    }
+                    Some(PixelZoom::FitSizeFloat { .. }) => {
+                        PixelZoom::FitSizeFloat { width, height }
+                    }
+                    Some(PixelZoom::FitSizeHybrid { tolerance, .. }) => PixelZoom::FitSizeHybrid {
+                        width,
+                        height,
+                        tolerance: *tolerance,
+                    },
+                    Some(PixelZoom::Expand { .. }) => PixelZoom::Expand {
+                        min_width: width,
+                        min_height: height,
+                    },
+                    Some(PixelZoom::Stretch { .. }) => PixelZoom::Stretch { width, height },
+                    _ => PixelZoom::FitSize { width, height },
+                };
+                entity.insert(pixel_zoom);
+            }
+            Change::ZoomMode(pixel_zoom) => {
+                entity.insert(pixel_zoom);
+            }
+            Change::Viewport(true) => {
+                entity.insert(PixelViewport::default());
+            }
+            Change::Viewport(false) => {
+                entity.remove::<PixelViewport>();
+                if let Some(mut camera) = entity.get_mut::<Camera>() {
+                    camera.viewport = None;
+                }
+            }
+            Change::LetterboxColor(color) => {
+                match entity.get_mut::<PixelLetterboxColor>() {
+                    Some(mut letterbox) => letterbox.color = color,
+                    None => {
+                        entity.insert(PixelLetterboxColor::new(color));
+                    }
+                }
+                return;
+            }
+            Change::ZoomPunch { strength, duration } => {
+                let mut punch = PixelZoomPunch::new(strength, duration);
+                if let Some(current) = entity.get::<PixelZoomPunch>() {
+                    // Keep the scale currently applied, so that it is
+                    // restored at the beginning of the next frame.
+                    punch.scale = current.scale;
+                }
+                entity.insert(punch);
+                return;
+            }
+        }
+
+        refresh_pixel_camera(world, self.camera);
+    }
+}
+
+/// Recompute the zoom, scaling mode and viewport of a camera right away, as
+/// `pixel_zoom_system` (and `split_screen_zoom_system`) would.
+fn refresh_pixel_camera(world: &mut World, entity: Entity) {
+    let _span = info_span!("refresh_pixel_camera", camera = ?entity).entered();
+    if !world.contains_resource::<PixelZoomOverride>() {
+        // The plugin is not added.
+        return;
+    }
+    let mut state = SystemState::<ZoomUpdate>::new(world);
+    state.get_mut(world).update_camera(entity, true, false);
+    state.apply(world);
+    if world.get::<PixelSplitScreen>(entity).is_some() {
+        world.run_system_once(split_screen_zoom_system);
+    }
+}
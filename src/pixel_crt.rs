@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::{PixelCanvas, PixelUpscaleMaterial};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Simulate a CRT screen when upscaling a `PixelCanvas`.
+///
+/// The effect is applied to the upscaled image (i.e. at the resolution of the
+/// window), and its parameters can be changed at any time. Removing the
+/// component disables the effect.
+///
+/// Only available with the `crt` feature.
+pub struct PixelCrt {
+    /// Amount of barrel distortion (0.0 for a flat screen).
+    pub curvature: f32,
+    /// Radius of the rounded corners of the screen, as a fraction of its
+    /// height.
+    pub corner_radius: f32,
+    /// Pattern of the phosphor mask.
+    pub mask: CrtMask,
+    /// How much the mask darkens the image, between 0.0 and 1.0.
+    pub mask_intensity: f32,
+    /// Amount of light bleeding around bright pixels.
+    pub bloom: f32,
+}
+
+impl Default for PixelCrt {
+    fn default() -> Self {
+        Self {
+            curvature: 0.05,
+            corner_radius: 0.03,
+            mask: CrtMask::ApertureGrille,
+            mask_intensity: 0.3,
+            bloom: 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The phosphor mask of a `PixelCrt` effect, drawn on physical pixels.
+pub enum CrtMask {
+    /// No mask.
+    None,
+    /// Vertical stripes of red, green and blue phosphors.
+    #[default]
+    ApertureGrille,
+    /// Aperture grille with staggered horizontal gaps.
+    SlotMask,
+}
+
+impl CrtMask {
+    fn index(self) -> u32 {
+        match self {
+            CrtMask::None => 0,
+            CrtMask::ApertureGrille => 1,
+            CrtMask::SlotMask => 2,
+        }
+    }
+}
+
+pub(crate) fn pixel_crt_system(
+    mut materials: ResMut<Assets<PixelUpscaleMaterial>>,
+    cameras: Query<(&PixelCanvas, Option<&PixelCrt>)>,
+) {
+    for (canvas, crt) in &cameras {
+        let Some(handle) = canvas.material() else {
+            continue;
+        };
+        let crt = crt.copied().unwrap_or(PixelCrt {
+            curvature: 0.0,
+            corner_radius: 0.0,
+            mask: CrtMask::None,
+            mask_intensity: 0.0,
+            bloom: 0.0,
+        });
+        PixelUpscaleMaterial::update(&mut materials, handle, |material| {
+            material.crt_curvature = crt.curvature;
+            material.crt_corner_radius = crt.corner_radius;
+            material.crt_mask = crt.mask.index();
+            material.crt_mask_intensity = crt.mask_intensity.clamp(0.0, 1.0);
+            material.crt_bloom = crt.bloom;
+        });
+    }
+}
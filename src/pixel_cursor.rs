@@ -0,0 +1,406 @@
+use bevy::input::touch::Touch;
+use bevy::prelude::*;
+use bevy::render::camera::{NormalizedRenderTarget, Projection};
+use bevy::transform::TransformSystem;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+
+use crate::{PixelCameraPlugin, PixelCameraSet, PixelCanvas, PixelZoom};
+
+/// Provides the software cursor (see `PixelCursor`).
+///
+/// This plugin also adds the `PixelCameraPlugin` if needed.
+pub struct PixelCursorPlugin;
+
+impl Plugin for PixelCursorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PixelCameraPlugin>() {
+            app.add_plugins(PixelCameraPlugin::default());
+        }
+        if !app.world.contains_resource::<VirtualCursor>() {
+            add_virtual_cursor(app);
+        }
+        app.add_systems(
+            PostUpdate,
+            pixel_cursor_system
+                .after(crate::pixel_snap::snap_camera_system)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Registers `VirtualCursor` and `VirtualTouches`, and the systems updating
+/// them.
+pub(crate) fn add_virtual_cursor(app: &mut App) {
+    app.init_resource::<VirtualCursor>()
+        .init_resource::<VirtualTouches>()
+        .add_systems(
+            PreUpdate,
+            (virtual_cursor_system, virtual_touches_system).after(PixelCameraSet::Restore),
+        );
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Use a sprite as the cursor, in place of the OS cursor.
+///
+/// Add this component to an entity with a `SpriteBundle`, rendered by a
+/// `PixelZoom` camera. The sprite follows the `VirtualCursor`, aligned on
+/// virtual pixels, and is hidden when the cursor leaves the viewport. The OS
+/// cursor is hidden while it is over the viewport, and shown again over the
+/// letterbox bars.
+///
+/// Requires the `PixelCursorPlugin`.
+pub struct PixelCursor {
+    /// The texel of the image pointing at the cursor position, from the
+    /// top-left corner of the image.
+    pub hotspot: UVec2,
+}
+
+impl PixelCursor {
+    /// A cursor with the specified hotspot.
+    pub fn with_hotspot(x: u32, y: u32) -> Self {
+        Self {
+            hotspot: UVec2::new(x, y),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+/// The position of the cursor in the world, and on the virtual pixel grid.
+///
+/// Updated at the beginning of each frame (in `PreUpdate`), through the
+/// topmost `PixelZoom` camera under the cursor. The conversion takes into
+/// account the position of the viewport (e.g. the letterbox bars), the zoom,
+/// and the upscaling of a `PixelCanvas`. All fields are `None` when the
+/// cursor is outside of the area rendered by the cameras.
+pub struct VirtualCursor {
+    /// The position of the cursor, in world coordinates.
+    pub world: Option<Vec2>,
+    /// The virtual pixel under the cursor, i.e. the world position divided by
+    /// the size of a virtual pixel, rounded down.
+    pub virtual_pixel: Option<IVec2>,
+    /// The camera under the cursor.
+    pub camera: Option<Entity>,
+}
+
+/// Convert a logical position in a window into a logical position in the
+/// viewport of a `PixelZoom` camera displayed in this window (i.e. relative
+/// to the top-left corner of the viewport, or of the image of a canvas).
+pub(crate) fn window_to_viewport(
+    camera: &Camera,
+    canvas: Option<&PixelCanvas>,
+    window: &Window,
+    position: Vec2,
+) -> Option<Vec2> {
+    match canvas {
+        Some(canvas) => canvas.window_to_image(position, window),
+        None => {
+            let viewport = camera.logical_viewport_rect()?;
+            viewport.contains(position).then(|| position - viewport.min)
+        }
+    }
+}
+
+/// Convert a logical position in a window into world coordinates, through a
+/// `PixelZoom` camera displayed in this window.
+pub(crate) fn window_to_world(
+    camera: &Camera,
+    transform: &GlobalTransform,
+    canvas: Option<&PixelCanvas>,
+    window: &Window,
+    position: Vec2,
+) -> Option<Vec2> {
+    let position = window_to_viewport(camera, canvas, window, position)?;
+    camera.viewport_to_world_2d(transform, position)
+}
+
+/// The window a `PixelZoom` camera is displayed in.
+pub(crate) fn camera_window(
+    camera: &Camera,
+    canvas: Option<&PixelCanvas>,
+    primary_window: Option<Entity>,
+) -> Option<Entity> {
+    match canvas {
+        Some(canvas) => canvas.window(),
+        None => match camera.target.normalize(primary_window)? {
+            NormalizedRenderTarget::Window(window_ref) => Some(window_ref.entity()),
+            _ => None,
+        },
+    }
+}
+
+/// The size of a virtual pixel in world units, i.e. the scale of the
+/// orthographic projection.
+pub(crate) fn virtual_pixel_scale(
+    orthographic: Option<&OrthographicProjection>,
+    projection: Option<&Projection>,
+) -> Option<f32> {
+    let pixel_size = match (orthographic, projection) {
+        (Some(orthographic), _) => orthographic.scale,
+        (None, Some(Projection::Orthographic(orthographic))) => orthographic.scale,
+        _ => return None,
+    };
+    (pixel_size > 0.0).then_some(pixel_size)
+}
+
+/// The virtual pixel containing a world position.
+pub(crate) fn world_to_virtual_pixel(
+    position: Vec2,
+    orthographic: Option<&OrthographicProjection>,
+    projection: Option<&Projection>,
+) -> Option<IVec2> {
+    let pixel_size = virtual_pixel_scale(orthographic, projection)?;
+    Some((position / pixel_size).floor().as_ivec2())
+}
+
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+/// The positions of the active touches in the world, and on the virtual pixel
+/// grid.
+///
+/// Updated at the beginning of each frame (in `PreUpdate`) from Bevy's
+/// `Touches`, with the same conversion as `VirtualCursor`. Touches are
+/// located in the primary window, and those outside of the area rendered by
+/// the `PixelZoom` cameras are ignored.
+pub struct VirtualTouches {
+    touches: Vec<VirtualTouch>,
+}
+
+impl VirtualTouches {
+    /// Iterate over the active touches rendered by a `PixelZoom` camera.
+    pub fn iter(&self) -> impl Iterator<Item = &VirtualTouch> + '_ {
+        self.touches.iter()
+    }
+
+    /// The touch with the specified id, if it is active and rendered by a
+    /// `PixelZoom` camera.
+    pub fn get(&self, id: u64) -> Option<&VirtualTouch> {
+        self.touches.iter().find(|touch| touch.id == id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An active touch, converted by `VirtualTouches`.
+pub struct VirtualTouch {
+    /// The id of the touch (see Bevy's `Touch::id`).
+    pub id: u64,
+    /// The position of the touch, in world coordinates.
+    pub world: Vec2,
+    /// The virtual pixel under the touch (if the camera has an orthographic
+    /// projection).
+    pub virtual_pixel: Option<IVec2>,
+    /// The camera under the touch.
+    pub camera: Entity,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) type CameraQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Camera,
+        &'static GlobalTransform,
+        Option<&'static PixelCanvas>,
+        Option<&'static OrthographicProjection>,
+        Option<&'static Projection>,
+    ),
+    With<PixelZoom>,
+>;
+
+/// A position in a window, converted through a `PixelZoom` camera.
+#[derive(Debug, Clone, Copy)]
+struct CameraHit {
+    camera: Entity,
+    world: Vec2,
+    virtual_pixel: Option<IVec2>,
+}
+
+/// Find the topmost `PixelZoom` camera displayed at a logical position of a
+/// window, and convert this position into world coordinates.
+fn topmost_camera_at(
+    cameras: &CameraQuery,
+    primary_window: Option<Entity>,
+    window_entity: Entity,
+    window: &Window,
+    position: Vec2,
+) -> Option<CameraHit> {
+    let mut topmost: Option<(isize, CameraHit)> = None;
+    for (entity, camera, transform, canvas, orthographic, projection) in cameras {
+        if !camera.is_active
+            || topmost.is_some_and(|(order, _)| order >= camera.order)
+            || camera_window(camera, canvas, primary_window) != Some(window_entity)
+        {
+            continue;
+        }
+        let Some(world) = window_to_world(camera, transform, canvas, window, position) else {
+            continue;
+        };
+        let hit = CameraHit {
+            camera: entity,
+            world,
+            virtual_pixel: world_to_virtual_pixel(world, orthographic, projection),
+        };
+        topmost = Some((camera.order, hit));
+    }
+    topmost.map(|(_, hit)| hit)
+}
+
+pub(crate) fn virtual_cursor_system(
+    mut cursor: ResMut<VirtualCursor>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<(Entity, &Window)>,
+    cameras: CameraQuery,
+) {
+    let primary_window = primary_window.iter().next();
+
+    let updated = windows
+        .iter()
+        .find_map(|(window_entity, window)| {
+            let position = window.cursor_position()?;
+            topmost_camera_at(&cameras, primary_window, window_entity, window, position)
+        })
+        .map_or_else(VirtualCursor::default, |hit| VirtualCursor {
+            world: Some(hit.world),
+            virtual_pixel: hit.virtual_pixel,
+            camera: Some(hit.camera),
+        });
+    if *cursor != updated {
+        *cursor = updated;
+    }
+}
+
+pub(crate) fn virtual_touches_system(
+    mut virtual_touches: ResMut<VirtualTouches>,
+    touches: Option<Res<Touches>>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    cameras: CameraQuery,
+) {
+    let Some(touches) = touches else {
+        return;
+    };
+    let primary = primary_window.iter().next();
+    let convert = |touch: &Touch| {
+        let (window_entity, window) = primary?;
+        let hit = topmost_camera_at(
+            &cameras,
+            Some(window_entity),
+            window_entity,
+            window,
+            touch.position(),
+        )?;
+        Some(VirtualTouch {
+            id: touch.id(),
+            world: hit.world,
+            virtual_pixel: hit.virtual_pixel,
+            camera: hit.camera,
+        })
+    };
+    let unchanged = virtual_touches
+        .touches
+        .iter()
+        .copied()
+        .eq(touches.iter().filter_map(convert));
+    if !unchanged {
+        virtual_touches.touches.clear();
+        virtual_touches
+            .touches
+            .extend(touches.iter().filter_map(convert));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_cursor_system(
+    virtual_cursor: Res<VirtualCursor>,
+    images: Res<Assets<Image>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut windows: Query<(Entity, &mut Window)>,
+    cameras: Query<
+        (
+            &Camera,
+            &Transform,
+            &GlobalTransform,
+            Option<&PixelCanvas>,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+        ),
+        With<PixelZoom>,
+    >,
+    mut cursors: Query<
+        (
+            &PixelCursor,
+            &Sprite,
+            &Handle<Image>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<PixelZoom>,
+    >,
+    // The windows whose OS cursor has been hidden by this system.
+    mut hidden: Local<HashSet<Entity>>,
+) {
+    if cursors.is_empty() {
+        // Show the OS cursor again, e.g. after the last cursor is despawned.
+        for entity in hidden.drain() {
+            if let Ok((_, mut window)) = windows.get_mut(entity) {
+                window.cursor.visible = true;
+            }
+        }
+        return;
+    }
+    let primary_window = primary_window.iter().next();
+
+    // The top-left corner of the virtual pixel under the cursor, relative to
+    // the camera that rendered it in the previous frame, and the camera
+    // translation for the current frame: the cursor stays still on screen
+    // when the camera moves.
+    let target = virtual_cursor
+        .camera
+        .zip(virtual_cursor.virtual_pixel)
+        .and_then(|(entity, virtual_pixel)| {
+            let (camera, transform, global_transform, canvas, orthographic, projection) =
+                cameras.get(entity).ok()?;
+            let pixel_size = virtual_pixel_scale(orthographic, projection)?;
+            let corner = Vec2::new(
+                virtual_pixel.x as f32 * pixel_size,
+                (virtual_pixel.y + 1) as f32 * pixel_size,
+            );
+            let offset = corner - global_transform.translation().truncate();
+            let window = camera_window(camera, canvas, primary_window);
+            Some((transform.translation.truncate() + offset, window))
+        });
+
+    for (window_entity, mut window) in &mut windows {
+        let over_viewport = target.is_some_and(|(_, window)| window == Some(window_entity));
+        if over_viewport && window.cursor.visible {
+            window.cursor.visible = false;
+            hidden.insert(window_entity);
+        } else if !over_viewport && hidden.remove(&window_entity) {
+            window.cursor.visible = true;
+        }
+    }
+
+    for (cursor, sprite, image, mut transform, mut visibility) in &mut cursors {
+        let image_size = images.get(image).map(|image| image.size().as_vec2());
+        let placement = target.zip(image_size).map(|((corner, _), image_size)| {
+            let size = sprite.custom_size.unwrap_or(image_size);
+            let texel_size = size / image_size.max(Vec2::ONE);
+            let hotspot = cursor.hotspot.as_vec2() * texel_size;
+            let top_left = corner + Vec2::new(-hotspot.x, hotspot.y);
+            let center = top_left + Vec2::new(size.x, -size.y) / 2.0;
+            center + sprite.anchor.as_vec() * size
+        });
+        let updated_visibility = match placement {
+            Some(translation) => {
+                let translation = translation.extend(transform.translation.z);
+                if transform.translation != translation {
+                    transform.translation = translation;
+                }
+                Visibility::Inherited
+            }
+            None => Visibility::Hidden,
+        };
+        if *visibility != updated_visibility {
+            *visibility = updated_visibility;
+        }
+    }
+}
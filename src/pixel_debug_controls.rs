@@ -0,0 +1,225 @@
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::{PixelViewport, PixelZoom};
+
+/// Provides keyboard and mouse wheel controls to change the `PixelZoom` of
+/// all cameras at runtime (see `PixelDebugControls`), e.g. to preview the game
+/// at each scale.
+///
+/// Only available with the `debug-controls` feature.
+pub struct PixelDebugControlsPlugin;
+
+impl Plugin for PixelDebugControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PixelDebugControls>()
+            .add_systems(Update, pixel_debug_controls_system);
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+/// The bindings of the `PixelDebugControlsPlugin`.
+pub struct PixelDebugControls {
+    /// Cycle through `PixelZoom::Fixed(1)` to `PixelZoom::Fixed(8)`.
+    pub cycle_fixed_zoom: KeyCode,
+    /// Cycle through the fit modes (`FitSize`, `FitSizeFloat`, `FitWidth`,
+    /// `FitHeight`, `Expand` and `Stretch`), for the resolution of the
+    /// original mode of the camera.
+    pub cycle_fit_mode: KeyCode,
+    /// Remove the `PixelViewport` of the cameras, or add it back.
+    pub toggle_viewport: KeyCode,
+    /// Restore the original mode and viewport of the cameras.
+    pub reset: KeyCode,
+    /// While this key is held, the mouse wheel increases or decreases the
+    /// fixed zoom (`None` to disable the mouse wheel).
+    pub wheel_modifier: Option<KeyCode>,
+}
+
+impl Default for PixelDebugControls {
+    fn default() -> Self {
+        Self {
+            cycle_fixed_zoom: KeyCode::F6,
+            cycle_fit_mode: KeyCode::F7,
+            toggle_viewport: KeyCode::F8,
+            reset: KeyCode::F9,
+            wheel_modifier: Some(KeyCode::ControlLeft),
+        }
+    }
+}
+
+/// The largest fixed zoom selected by the debug controls.
+const MAX_FIXED_ZOOM: i32 = 8;
+
+/// The settings of a camera before it was modified by the debug controls.
+#[derive(Component, Debug, Clone)]
+pub(crate) struct DebugZoomState {
+    original: PixelZoom,
+    original_viewport: Option<PixelViewport>,
+    /// The viewport removed by `toggle_viewport`.
+    hidden_viewport: Option<PixelViewport>,
+    /// The resolution used by the fit modes.
+    resolution: IVec2,
+    fit_mode: usize,
+}
+
+impl DebugZoomState {
+    fn fit_mode(&self, index: usize) -> PixelZoom {
+        let IVec2 {
+            x: width,
+            y: height,
+        } = self.resolution;
+        match index % 6 {
+            0 => PixelZoom::FitSize { width, height },
+            1 => PixelZoom::FitSizeFloat { width, height },
+            2 => PixelZoom::FitWidth(width),
+            3 => PixelZoom::FitHeight(height),
+            4 => PixelZoom::Expand {
+                min_width: width,
+                min_height: height,
+            },
+            _ => PixelZoom::Stretch { width, height },
+        }
+    }
+}
+
+/// The resolution targeted by a `PixelZoom` mode, if it specifies one.
+fn mode_resolution(mode: &PixelZoom) -> Option<IVec2> {
+    match mode {
+        PixelZoom::FitSize { width, height }
+        | PixelZoom::FitSizeFloat { width, height }
+        | PixelZoom::FitSizeHybrid { width, height, .. }
+        | PixelZoom::Stretch { width, height } => Some(IVec2::new(*width, *height)),
+        PixelZoom::Expand {
+            min_width,
+            min_height,
+        } => Some(IVec2::new(*min_width, *min_height)),
+        PixelZoom::FitBest(resolutions) => resolutions.first().map(|size| size.as_ivec2()),
+        _ => None,
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_debug_controls_system(
+    mut commands: Commands,
+    controls: Res<PixelDebugControls>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut cameras: Query<(
+        Entity,
+        &mut PixelZoom,
+        Option<&PixelViewport>,
+        Option<&DebugZoomState>,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+) {
+    let Some(keys) = keys else {
+        return;
+    };
+    let wheel: f32 = wheel_events.read().map(|event| event.y).sum();
+    let wheel_step = match controls.wheel_modifier {
+        Some(modifier) if keys.pressed(modifier) && wheel != 0.0 => wheel.signum() as i32,
+        _ => 0,
+    };
+    let cycle_fixed = keys.just_pressed(controls.cycle_fixed_zoom);
+    let cycle_fit = keys.just_pressed(controls.cycle_fit_mode);
+    let toggle_viewport = keys.just_pressed(controls.toggle_viewport);
+    let reset = keys.just_pressed(controls.reset);
+    if !(cycle_fixed || cycle_fit || toggle_viewport || reset || wheel_step != 0) {
+        return;
+    }
+
+    for (entity, mut pixel_zoom, viewport, state, orthographic, projection) in &mut cameras {
+        let mut state = match state {
+            Some(state) => state.clone(),
+            None => {
+                // Without a resolution in the mode, use the visible area.
+                let visible = match (orthographic, projection) {
+                    (Some(orthographic), _) => Some(orthographic),
+                    (None, Some(Projection::Orthographic(orthographic))) => Some(orthographic),
+                    _ => None,
+                }
+                .filter(|orthographic| orthographic.scale > 0.0)
+                .map(|orthographic| {
+                    (orthographic.area.size() / orthographic.scale)
+                        .round()
+                        .as_ivec2()
+                });
+                DebugZoomState {
+                    original: pixel_zoom.clone(),
+                    original_viewport: viewport.cloned(),
+                    hidden_viewport: None,
+                    resolution: mode_resolution(&pixel_zoom)
+                        .or(visible)
+                        .unwrap_or(IVec2::new(320, 180))
+                        .max(IVec2::ONE),
+                    fit_mode: 0,
+                }
+            }
+        };
+
+        let fixed_zoom = match *pixel_zoom {
+            PixelZoom::Fixed(zoom) => Some(zoom),
+            _ => None,
+        };
+        let mut updated = None;
+        if reset {
+            updated = Some(state.original.clone());
+            match &state.original_viewport {
+                Some(original) if viewport != Some(original) => {
+                    commands.entity(entity).insert(original.clone());
+                }
+                None if viewport.is_some() => {
+                    commands.entity(entity).remove::<PixelViewport>();
+                }
+                _ => {}
+            }
+        } else {
+            if cycle_fixed {
+                let zoom = fixed_zoom.map_or(1, |zoom| zoom % MAX_FIXED_ZOOM + 1);
+                updated = Some(PixelZoom::Fixed(zoom));
+            }
+            if wheel_step != 0 {
+                let zoom = fixed_zoom.unwrap_or(1) + wheel_step;
+                updated = Some(PixelZoom::Fixed(zoom.clamp(1, MAX_FIXED_ZOOM)));
+            }
+            if cycle_fit {
+                updated = Some(state.fit_mode(state.fit_mode));
+                state.fit_mode += 1;
+            }
+            if toggle_viewport {
+                match viewport {
+                    Some(viewport) => {
+                        state.hidden_viewport = Some(viewport.clone());
+                        commands.entity(entity).remove::<PixelViewport>();
+                    }
+                    None => {
+                        let viewport = state
+                            .hidden_viewport
+                            .take()
+                            .or_else(|| state.original_viewport.clone())
+                            .unwrap_or_default();
+                        commands.entity(entity).insert(viewport);
+                    }
+                }
+                info!(
+                    "Pixel camera {entity:?}: viewport {}",
+                    if viewport.is_some() {
+                        "disabled"
+                    } else {
+                        "enabled"
+                    }
+                );
+            }
+        }
+
+        if let Some(updated) = updated {
+            if *pixel_zoom != updated {
+                info!("Pixel camera {entity:?}: {updated:?}");
+                *pixel_zoom = updated;
+            }
+        }
+        commands.entity(entity).insert(state);
+    }
+}
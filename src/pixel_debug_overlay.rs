@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::{PixelCameraInfo, PixelZoom};
+
+/// Displays an on-screen overlay with the state of the `PixelZoom` cameras:
+/// zoom, virtual resolution and viewport of each camera, and the size and
+/// scale factor of the primary window (see `PixelDebugOverlay`).
+///
+/// The text uses the default font of Bevy, unless another one is specified in
+/// `PixelDebugOverlay::font`.
+///
+/// Only available with the `debug-overlay` feature.
+pub struct PixelDebugOverlayPlugin;
+
+impl Plugin for PixelDebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PixelDebugOverlay>()
+            .add_systems(Update, (toggle_debug_overlay, update_debug_overlay).chain());
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+/// The settings of the `PixelDebugOverlayPlugin`.
+pub struct PixelDebugOverlay {
+    /// Whether the overlay is shown.
+    pub visible: bool,
+    /// Show or hide the overlay.
+    pub toggle: KeyCode,
+    /// The font of the overlay (the default handle is Bevy's default font).
+    pub font: Handle<Font>,
+    /// The size of the text, in logical pixels.
+    pub font_size: f32,
+    /// The color of the text.
+    pub color: Color,
+    /// The camera displaying the overlay (by default, the one chosen by
+    /// `bevy_ui`). Useful when the main camera renders to a `PixelCanvas`.
+    pub camera: Option<Entity>,
+}
+
+impl Default for PixelDebugOverlay {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            toggle: KeyCode::F10,
+            font: Handle::default(),
+            font_size: 16.0,
+            color: Color::WHITE,
+            camera: None,
+        }
+    }
+}
+
+/// The text node of the overlay.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DebugOverlayText;
+
+fn toggle_debug_overlay(
+    mut overlay: ResMut<PixelDebugOverlay>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+) {
+    if keys.is_some_and(|keys| keys.just_pressed(overlay.toggle)) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_debug_overlay(
+    mut commands: Commands,
+    overlay: Res<PixelDebugOverlay>,
+    windows: Query<(Entity, &Window, Has<PrimaryWindow>)>,
+    cameras: Query<(Entity, &Camera, &PixelZoom, Option<&PixelCameraInfo>)>,
+    mut texts: Query<(Entity, &mut Text, &mut Visibility), With<DebugOverlayText>>,
+) {
+    let Ok((entity, mut text, mut visibility)) = texts.get_single_mut() else {
+        if overlay.visible {
+            let mut node = commands.spawn((
+                Name::new("Pixel Debug Overlay"),
+                DebugOverlayText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: overlay.font.clone(),
+                        font_size: overlay.font_size,
+                        color: overlay.color,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(4.0),
+                    left: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..Default::default()
+                })
+                .with_background_color(Color::rgba(0.0, 0.0, 0.0, 0.6)),
+                ZIndex::Global(i32::MAX),
+            ));
+            if let Some(camera) = overlay.camera {
+                node.insert(TargetCamera(camera));
+            }
+        }
+        return;
+    };
+
+    let wanted = if overlay.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility != wanted {
+        *visibility = wanted;
+    }
+    if !overlay.visible {
+        return;
+    }
+    if overlay.is_changed() {
+        let style = &mut text.sections[0].style;
+        style.font = overlay.font.clone();
+        style.font_size = overlay.font_size;
+        style.color = overlay.color;
+        match overlay.camera {
+            Some(camera) => commands.entity(entity).insert(TargetCamera(camera)),
+            None => commands.entity(entity).remove::<TargetCamera>(),
+        };
+    }
+
+    let mut value = String::new();
+    for (entity, window, primary) in &windows {
+        let _ = writeln!(
+            value,
+            "window {:?}{}: {} x {} (scale factor {})",
+            entity,
+            if primary { " (primary)" } else { "" },
+            window.width(),
+            window.height(),
+            window.scale_factor(),
+        );
+    }
+    let mut cameras: Vec<_> = cameras.iter().collect();
+    cameras.sort_by_key(|(_, camera, ..)| camera.order);
+    for (entity, camera, pixel_zoom, info) in cameras {
+        let _ = writeln!(
+            value,
+            "camera {:?} (order {}, target {:?}):",
+            entity, camera.order, camera.target,
+        );
+        let _ = writeln!(value, "  mode: {:?}", pixel_zoom);
+        let Some(info) = info else {
+            let _ = writeln!(value, "  (not configured yet)");
+            continue;
+        };
+        let _ = writeln!(value, "  zoom: {} x {}", info.zoom.x, info.zoom.y);
+        let _ = writeln!(
+            value,
+            "  virtual resolution: {} x {}",
+            info.virtual_size.x, info.virtual_size.y,
+        );
+        let _ = writeln!(
+            value,
+            "  viewport: {}, {} to {}, {}",
+            info.viewport_rect.min.x,
+            info.viewport_rect.min.y,
+            info.viewport_rect.max.x,
+            info.viewport_rect.max.y,
+        );
+    }
+    let value = value.trim_end();
+    if text.sections[0].value != value {
+        text.sections[0].value = value.to_string();
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::core_pipeline::core_2d::Camera2d;
+use bevy::prelude::*;
+
+use crate::{PixelViewport, PixelZoom};
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+/// The configuration given to the 2D cameras spawned without a `PixelZoom`,
+/// e.g. by scenes or third-party plugins.
+///
+/// When this resource is present, a clone of `zoom` (and of `viewport`, if
+/// any) is added to each new `Camera2d` that has no `PixelZoom`, before the
+/// zoom is computed in the same frame. Cameras spawned before the resource is
+/// inserted are not modified, nor are the cameras spawned by the crate (e.g.
+/// for the letterbox bars or a `PixelCanvas`).
+pub struct DefaultPixelZoom {
+    /// The zoom mode of the cameras.
+    pub zoom: PixelZoom,
+    /// The viewport of the cameras, if any.
+    pub viewport: Option<PixelViewport>,
+}
+
+impl DefaultPixelZoom {
+    /// Use `zoom`, without a `PixelViewport`.
+    pub fn new(zoom: PixelZoom) -> Self {
+        Self {
+            zoom,
+            viewport: None,
+        }
+    }
+
+    /// Also add a `PixelViewport`.
+    pub fn with_viewport(self, viewport: PixelViewport) -> Self {
+        Self {
+            viewport: Some(viewport),
+            ..self
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// A camera spawned by the crate itself (e.g. to draw the letterbox bars or
+/// display a canvas), which must never be given a `PixelZoom`.
+pub(crate) struct HelperCamera;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn default_pixel_zoom_system(
+    mut commands: Commands,
+    defaults: Option<Res<DefaultPixelZoom>>,
+    cameras: Query<Entity, (Added<Camera2d>, Without<PixelZoom>, Without<HelperCamera>)>,
+) {
+    let Some(defaults) = defaults else {
+        return;
+    };
+    for entity in &cameras {
+        let mut camera = commands.entity(entity);
+        camera.insert(defaults.zoom.clone());
+        if let Some(viewport) = &defaults.viewport {
+            camera.insert(viewport.clone());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::asset::load_internal_asset;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::extract_component::{ExtractComponent, ExtractComponentPlugin};
+use bevy::render::render_graph::RenderLabel;
+
+use crate::post_process::{physical_pixels_per_unit, PostProcess, PostProcessPlugin};
+
+const PIXEL_DITHER_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x27c4_8a1e_63f9_4d05_b2a7_e8d3_0c6f_914b);
+
+/// Provides the dithering post-processing pass (see `PixelDither`).
+pub struct PixelDitherPlugin;
+
+impl Plugin for PixelDitherPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PIXEL_DITHER_SHADER_HANDLE,
+            "pixel_dither.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins((
+            ExtractComponentPlugin::<PixelDither>::default(),
+            PostProcessPlugin::<DitherUniform>::default(),
+        ));
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// Reduce the number of colors rendered by a 2D camera, with ordered
+/// dithering.
+///
+/// Each channel is quantized to the specified number of bits, after being
+/// offset by a 4x4 Bayer matrix aligned on the virtual pixel grid. Gradients
+/// (e.g. from lighting or alpha blending) are then rendered with the dithered
+/// patterns typical of low color depths, instead of bands.
+///
+/// This is a post-processing pass, applied after tonemapping, independently of
+/// `PixelPalette` (combining both on the same camera is not useful: use the
+/// dithering of the palette instead). When both plugins are added, the
+/// dithering pass always runs before the palette pass.
+///
+/// Requires the `PixelDitherPlugin`.
+pub struct PixelDither {
+    /// Number of bits of the red, green and blue channels (between 1 and 8).
+    pub bits: UVec3,
+}
+
+impl PixelDither {
+    /// Quantize each channel to the same number of bits.
+    pub fn per_channel(bits: u32) -> Self {
+        Self {
+            bits: UVec3::splat(bits),
+        }
+    }
+
+    /// Quantize to 8-bit colors: 3 bits of red, 3 bits of green and 2 bits of
+    /// blue.
+    pub fn rgb332() -> Self {
+        Self {
+            bits: UVec3::new(3, 3, 2),
+        }
+    }
+}
+
+impl Default for PixelDither {
+    fn default() -> Self {
+        Self::per_channel(4)
+    }
+}
+
+// The `ShaderType` derive generates items that trigger the `dead_code` lint.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::prelude::*;
+    use bevy::render::render_resource::ShaderType;
+
+    /// The uniform extracted from a `PixelDither`.
+    #[doc(hidden)]
+    #[derive(Component, ShaderType, Clone)]
+    pub struct DitherUniform {
+        pub(crate) pixel_size: Vec2,
+        pub(crate) origin: Vec2,
+        pub(crate) levels: Vec3,
+    }
+}
+use uniform::DitherUniform;
+
+impl ExtractComponent for PixelDither {
+    type QueryData = (
+        &'static Self,
+        &'static Camera,
+        Option<&'static OrthographicProjection>,
+        Option<&'static Projection>,
+    );
+    type QueryFilter = ();
+    type Out = DitherUniform;
+
+    fn extract_component(
+        (dither, camera, orthographic, projection): QueryItem<Self::QueryData>,
+    ) -> Option<Self::Out> {
+        let viewport = camera.physical_viewport_rect()?;
+        let bits = dither.bits.clamp(UVec3::ONE, UVec3::splat(8));
+        Some(DitherUniform {
+            pixel_size: physical_pixels_per_unit(camera, orthographic, projection),
+            origin: viewport.min.as_vec2(),
+            levels: UVec3::new((1 << bits.x) - 1, (1 << bits.y) - 1, (1 << bits.z) - 1).as_vec3(),
+        })
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Default, RenderLabel)]
+pub(crate) struct DitherLabel;
+
+impl PostProcess for DitherUniform {
+    type Label = DitherLabel;
+    const NAME: &'static str = "pixel_dither";
+    const SHADER: Handle<Shader> = PIXEL_DITHER_SHADER_HANDLE;
+}
@@ -0,0 +1,45 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct PixelDither {
+    // Size of a virtual pixel, in physical pixels.
+    pixel_size: vec2<f32>,
+    // Top-left corner of the viewport, in physical pixels.
+    origin: vec2<f32>,
+    // Number of quantization steps of each channel (2^bits - 1).
+    levels: vec3<f32>,
+};
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var screen_sampler: sampler;
+@group(0) @binding(2) var<uniform> dither: PixelDither;
+
+// Threshold of the 4x4 Bayer matrix, between -0.5 and 0.5.
+fn bayer4(cell: vec2<u32>) -> f32 {
+    let x = cell.x % 4u;
+    let y = cell.y % 4u;
+    let a = x ^ y;
+    let index = ((a & 1u) << 3u) | ((y & 1u) << 2u) | (a & 2u) | ((y & 2u) >> 1u);
+    return (f32(index) + 0.5) / 16.0 - 0.5;
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3(0.0031308));
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3(2.4));
+    return select(high, low, color <= vec3(0.04045));
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(screen_texture, screen_sampler, in.uv);
+    let cell = vec2<u32>(max(floor((in.position.xy - dither.origin) / dither.pixel_size), vec2(0.0)));
+    // Bit depths are defined on the encoded values, not on linear ones.
+    let encoded = linear_to_srgb(clamp(color.rgb, vec3(0.0), vec3(1.0)));
+    let quantized = floor(encoded * dither.levels + 0.5 + bayer4(cell)) / dither.levels;
+    return vec4(srgb_to_linear(clamp(quantized, vec3(0.0), vec3(1.0))), color.a);
+}
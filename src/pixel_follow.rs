@@ -0,0 +1,245 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::pixel_cursor::virtual_pixel_scale;
+use crate::{PixelZoom, SnapCameraToPixelGrid};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Move a `PixelZoom` camera to follow an entity.
+///
+/// The camera only moves when the target leaves the dead zone, i.e. a
+/// rectangle around the center of the camera, specified in virtual pixels.
+/// The camera is moved in `PostUpdate`, before it is snapped to the pixel
+/// grid (see `SnapCameraToPixelGrid`), using the `Transform` of the target
+/// (it should not be the child of a moving entity).
+///
+/// With a `smoothing` or a `look_ahead`, the camera eases toward the target.
+/// A `SnapCameraToPixelGrid` is then added to the camera if needed, so that
+/// the rendered position still lands on whole virtual pixels.
+pub struct PixelCameraFollow {
+    /// The entity followed by the camera.
+    pub target: Entity,
+    /// The area where the target can move without moving the camera, in
+    /// virtual pixels relative to the center of the camera.
+    pub dead_zone: Rect,
+    /// How the camera moves toward the target.
+    pub smoothing: FollowSmoothing,
+    /// Move the camera ahead of the target, in the direction it is moving.
+    pub look_ahead: Option<FollowLookAhead>,
+}
+
+impl PixelCameraFollow {
+    /// Keep `target` at the center of the camera.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            dead_zone: Rect::default(),
+            smoothing: FollowSmoothing::None,
+            look_ahead: None,
+        }
+    }
+
+    /// Use a dead zone of the specified size (in virtual pixels), centered on
+    /// the camera.
+    pub fn with_dead_zone(self, width: f32, height: f32) -> Self {
+        Self {
+            dead_zone: Rect::from_center_size(Vec2::ZERO, Vec2::new(width, height)),
+            ..self
+        }
+    }
+
+    /// Ease the camera toward the target.
+    pub fn with_smoothing(self, smoothing: FollowSmoothing) -> Self {
+        Self { smoothing, ..self }
+    }
+
+    /// Move the camera ahead of the target.
+    pub fn with_look_ahead(self, look_ahead: FollowLookAhead) -> Self {
+        Self {
+            look_ahead: Some(look_ahead),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// How a `PixelCameraFollow` camera moves toward its target.
+pub enum FollowSmoothing {
+    /// Move the camera immediately.
+    #[default]
+    None,
+    /// Cover half of the remaining distance every `half_life` seconds (fast
+    /// at first, then slowing down).
+    Exponential { half_life: f32 },
+    /// Move like a critically damped spring (accelerating, then slowing
+    /// down without overshooting), reaching the target in about
+    /// `smooth_time` seconds.
+    CriticallyDamped { smooth_time: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Bias a `PixelCameraFollow` camera ahead of its target, based on the
+/// velocity of the target.
+pub struct FollowLookAhead {
+    /// The offset is the distance covered by the target in this duration, in
+    /// seconds.
+    pub time: f32,
+    /// The maximum offset, in virtual pixels.
+    pub max_offset: Vec2,
+    /// The offset covers half of the distance to its new value every
+    /// `half_life` seconds (zero for no smoothing).
+    pub half_life: f32,
+}
+
+impl Default for FollowLookAhead {
+    fn default() -> Self {
+        Self {
+            time: 0.5,
+            max_offset: Vec2::new(48.0, 24.0),
+            half_life: 0.25,
+        }
+    }
+}
+
+/// The previous position of the target of a `FollowLookAhead`, and the
+/// current offset.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct LookAheadState {
+    previous_target: Option<Vec2>,
+    offset: Vec2,
+}
+
+/// The velocity of a camera following its target with a critically damped
+/// spring.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct FollowVelocity(Vec2);
+
+/// The translation of a camera bringing `target` back into the dead zone.
+fn follow(camera: Vec2, target: Vec2, dead_zone: Rect) -> Vec2 {
+    let offset = target - camera;
+    camera + offset - offset.clamp(dead_zone.min, dead_zone.max)
+}
+
+/// Move `current` toward `target` with a critically damped spring (see Game
+/// Programming Gems 4, "Critically Damped Ease-In/Ease-Out Smoothing").
+fn smooth_damp(
+    current: Vec2,
+    target: Vec2,
+    velocity: &mut Vec2,
+    smooth_time: f32,
+    dt: f32,
+) -> Vec2 {
+    let omega = 2.0 / smooth_time.max(1e-4);
+    let x = omega * dt;
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * dt;
+    *velocity = (*velocity - omega * temp) * decay;
+    target + (change + temp) * decay
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_camera_follow_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<
+        (
+            Entity,
+            &PixelCameraFollow,
+            &mut Transform,
+            Option<&mut FollowVelocity>,
+            Option<&mut LookAheadState>,
+            Has<SnapCameraToPixelGrid>,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+        ),
+        With<PixelZoom>,
+    >,
+    targets: Query<&Transform, Without<PixelZoom>>,
+) {
+    let dt = time.delta_seconds();
+    for (
+        entity,
+        follow_settings,
+        mut transform,
+        velocity,
+        look_ahead_state,
+        snapped,
+        orthographic,
+        projection,
+    ) in &mut cameras
+    {
+        let Ok(target) = targets.get(follow_settings.target) else {
+            continue;
+        };
+        let pixel_size = virtual_pixel_scale(orthographic, projection).unwrap_or(1.0);
+        let dead_zone = Rect {
+            min: follow_settings.dead_zone.min * pixel_size,
+            max: follow_settings.dead_zone.max * pixel_size,
+        };
+        let target = target.translation.truncate();
+
+        let mut look_ahead = Vec2::ZERO;
+        if let Some(settings) = follow_settings.look_ahead {
+            let mut state = look_ahead_state.as_deref().copied().unwrap_or_default();
+            let target_velocity = match state.previous_target {
+                Some(previous) if dt > 0.0 => (target - previous) / dt,
+                _ => Vec2::ZERO,
+            };
+            let max_offset = settings.max_offset * pixel_size;
+            let wanted = (target_velocity * settings.time).clamp(-max_offset, max_offset);
+            state.offset = if settings.half_life > 0.0 {
+                state
+                    .offset
+                    .lerp(wanted, 1.0 - 0.5f32.powf(dt / settings.half_life))
+            } else {
+                wanted
+            };
+            state.previous_target = Some(target);
+            look_ahead = state.offset;
+            match look_ahead_state {
+                Some(mut current_state) => *current_state = state,
+                None => {
+                    commands.entity(entity).insert(state);
+                }
+            }
+        }
+
+        let current = transform.translation.truncate();
+        let desired = follow(current, target + look_ahead, dead_zone);
+
+        let smoothed = match follow_settings.smoothing {
+            FollowSmoothing::None => desired,
+            FollowSmoothing::Exponential { half_life } if half_life > 0.0 => {
+                current.lerp(desired, 1.0 - 0.5f32.powf(dt / half_life))
+            }
+            FollowSmoothing::Exponential { .. } => desired,
+            FollowSmoothing::CriticallyDamped { smooth_time } => {
+                let mut updated_velocity = velocity.as_deref().map_or(Vec2::ZERO, |v| v.0);
+                let smoothed =
+                    smooth_damp(current, desired, &mut updated_velocity, smooth_time, dt);
+                match velocity {
+                    Some(mut velocity) => velocity.0 = updated_velocity,
+                    None => {
+                        commands
+                            .entity(entity)
+                            .insert(FollowVelocity(updated_velocity));
+                    }
+                }
+                smoothed
+            }
+        };
+        let smooth = follow_settings.smoothing != FollowSmoothing::None
+            || follow_settings.look_ahead.is_some();
+        if smooth && !snapped {
+            commands
+                .entity(entity)
+                .insert(SnapCameraToPixelGrid::default());
+        }
+
+        let translation = smoothed.extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy::render::camera::{camera_system, CameraUpdateSystem, ManualTextureViews};
+use bevy::window::{
+    PrimaryWindow, WindowCreated, WindowResized, WindowResolution, WindowScaleFactorChanged,
+};
+
+/// A plugin to run `PixelCameraPlugin` without a window or GPU, e.g. to test
+/// the camera setup of a game in CI.
+///
+/// It adds the parts of Bevy's `RenderPlugin` and `WindowPlugin` that the
+/// cameras need to know the size of their render target: add it with
+/// `MinimalPlugins`, `AssetPlugin` and `PixelCameraPlugin`, then spawn the
+/// windows with `spawn_headless_window`. Don't use it with `DefaultPlugins`.
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy::render::camera::ScalingMode;
+/// use bevy_pixel_camera::{
+///     resize_headless_window, spawn_headless_window, PixelCameraHeadlessPlugin,
+///     PixelCameraPlugin, PixelZoom,
+/// };
+///
+/// let mut app = App::new();
+/// app.add_plugins((
+///     MinimalPlugins,
+///     AssetPlugin::default(),
+///     PixelCameraHeadlessPlugin,
+///     PixelCameraPlugin::default(),
+/// ));
+/// let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+/// let camera = app
+///     .world
+///     .spawn((Camera2dBundle::default(), PixelZoom::FitSize { width: 320, height: 180 }))
+///     .id();
+/// app.update();
+///
+/// resize_headless_window(&mut app.world, window, 1280.0, 720.0);
+/// app.update();
+/// let projection = app.world.get::<OrthographicProjection>(camera).unwrap();
+/// assert!(matches!(projection.scaling_mode, ScalingMode::WindowSize(zoom) if zoom == 4.0));
+/// ```
+pub struct PixelCameraHeadlessPlugin;
+
+impl Plugin for PixelCameraHeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Assets<Image>>() {
+            app.init_asset::<Image>();
+        }
+        if !app.world.contains_resource::<Assets<TextureAtlasLayout>>() {
+            app.init_asset::<TextureAtlasLayout>();
+        }
+        app.init_resource::<ManualTextureViews>()
+            .add_event::<WindowResized>()
+            .add_event::<WindowCreated>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_systems(
+                PostUpdate,
+                (
+                    camera_system::<OrthographicProjection>,
+                    camera_system::<Projection>,
+                )
+                    .in_set(CameraUpdateSystem),
+            );
+    }
+}
+
+/// Spawn a primary window of `width` x `height` logical pixels, for use with
+/// `PixelCameraHeadlessPlugin`.
+pub fn spawn_headless_window(world: &mut World, width: f32, height: f32) -> Entity {
+    world
+        .spawn((
+            Window {
+                resolution: WindowResolution::new(width, height),
+                ..Default::default()
+            },
+            PrimaryWindow,
+        ))
+        .id()
+}
+
+/// Resize a window to `width` x `height` logical pixels, as `WindowPlugin`
+/// would do. The cameras are updated during the next `App::update`.
+pub fn resize_headless_window(world: &mut World, window: Entity, width: f32, height: f32) {
+    let Some(mut window_component) = world.get_mut::<Window>(window) else {
+        return;
+    };
+    window_component.resolution.set(width, height);
+    world.send_event(WindowResized {
+        window,
+        width,
+        height,
+    });
+}
+
+/// Change the scale factor of a window, keeping its logical size, as
+/// `WindowPlugin` would do. The cameras are updated during the next
+/// `App::update`.
+pub fn set_headless_scale_factor(world: &mut World, window: Entity, scale_factor: f32) {
+    let Some(mut window_component) = world.get_mut::<Window>(window) else {
+        return;
+    };
+    window_component
+        .resolution
+        .set_scale_factor_override(Some(scale_factor));
+    world.send_event(WindowScaleFactorChanged {
+        window,
+        scale_factor: scale_factor as f64,
+    });
+}
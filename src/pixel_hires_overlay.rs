@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, RenderTarget, ScalingMode, Viewport};
+use bevy::render::view::RenderLayers;
+use bevy::window::{PrimaryWindow, WindowRef};
+
+use crate::pixel_default::HelperCamera;
+use crate::pixel_zoom::same_viewport;
+use crate::{NoPixelSnap, PixelCanvas, PixelZoom};
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+/// Render a high resolution overlay (e.g. crisp text or particles) over the
+/// viewport of a `PixelZoom` camera.
+///
+/// With this component, an additional unscaled `Camera2d` is spawned, rendered
+/// after the pixel camera (and its `PixelLetterboxColor`, if any) to the same
+/// target, with the same viewport. It only renders the entities on the render
+/// layer `layer`: one world unit is one logical pixel, with the origin at the
+/// center of the viewport. The viewport of the overlay is kept in sync with
+/// the pixel camera, e.g. after a resize. The overlay camera is despawned with
+/// the component.
+///
+/// With a `PixelCanvas`, the overlay is rendered to the window at its native
+/// resolution, over the upscaled canvas, and clipped to the area covered by
+/// the canvas. Being the topmost camera of the window, it is also the default
+/// camera of `bevy_ui`, so the user interface is laid out in that area, with
+/// crisp text over the chunky pixels of the world.
+pub struct PixelHiResOverlay {
+    /// The render layer of the overlay.
+    pub layer: u8,
+    overlay: Option<Entity>,
+}
+
+impl PixelHiResOverlay {
+    /// An overlay on the specified render layer.
+    pub fn new(layer: u8) -> Self {
+        Self {
+            layer,
+            overlay: None,
+        }
+    }
+
+    /// The overlay camera, once spawned.
+    pub fn camera(&self) -> Option<Entity> {
+        self.overlay
+    }
+}
+
+impl Default for PixelHiResOverlay {
+    fn default() -> Self {
+        Self::new(RenderLayers::TOTAL_LAYERS as u8 - 4)
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The camera of a `PixelHiResOverlay`.
+pub(crate) struct HiResOverlayCamera {
+    camera: Entity,
+}
+
+/// The target and viewport of the overlay: those of the pixel camera, or the
+/// area of the window covered by its canvas.
+fn overlay_target(
+    camera: &Camera,
+    canvas: Option<&PixelCanvas>,
+    windows: &Query<&Window>,
+) -> (RenderTarget, Option<Viewport>) {
+    let Some((entity, window)) = canvas
+        .and_then(|canvas| canvas.window())
+        .and_then(|entity| Some((entity, windows.get(entity).ok()?)))
+    else {
+        return (camera.target.clone(), camera.viewport.clone());
+    };
+    let target = RenderTarget::Window(WindowRef::Entity(entity));
+    let Some(rect) = canvas.and_then(|canvas| canvas.display_rect(window)) else {
+        return (target, None);
+    };
+    let physical_size = UVec2::new(window.physical_width(), window.physical_height());
+    let to_physical = |point: Vec2| {
+        (point * window.scale_factor())
+            .round()
+            .clamp(Vec2::ZERO, physical_size.as_vec2())
+            .as_uvec2()
+    };
+    // The canvas is centered in the window, and may be larger than it.
+    let (min, max) = (to_physical(rect.min), to_physical(rect.max));
+    let viewport = (min != UVec2::ZERO || max != physical_size).then(|| Viewport {
+        physical_position: min,
+        physical_size: (max - min).max(UVec2::ONE),
+        ..Default::default()
+    });
+    (target, viewport)
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_hires_overlay_system(
+    mut commands: Commands,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    mut cameras: Query<
+        (
+            Entity,
+            &Camera,
+            Option<&PixelCanvas>,
+            &mut PixelHiResOverlay,
+        ),
+        With<PixelZoom>,
+    >,
+    mut overlays: Query<
+        (Entity, &HiResOverlayCamera, &mut Camera, &mut RenderLayers),
+        Without<PixelZoom>,
+    >,
+) {
+    for (entity, overlay, ..) in &overlays {
+        let removed = cameras
+            .get(overlay.camera)
+            .map_or(true, |(.., hires)| hires.overlay != Some(entity));
+        if removed {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let primary_window = primary_window.iter().next();
+    for (entity, camera, canvas, mut hires) in &mut cameras {
+        let (target, viewport) = overlay_target(camera, canvas, &windows);
+        let Some(overlay) = hires.overlay else {
+            let overlay = commands
+                .spawn((
+                    Name::new("High Resolution Overlay Camera"),
+                    HelperCamera,
+                    HiResOverlayCamera { camera: entity },
+                    NoPixelSnap,
+                    Camera2dBundle {
+                        camera: Camera {
+                            order: camera.order + 2,
+                            target,
+                            viewport,
+                            clear_color: ClearColorConfig::None,
+                            ..Default::default()
+                        },
+                        projection: OrthographicProjection {
+                            scaling_mode: ScalingMode::WindowSize(1.0),
+                            ..Camera2dBundle::default().projection
+                        },
+                        ..Default::default()
+                    },
+                    RenderLayers::layer(hires.layer),
+                ))
+                .id();
+            hires.overlay = Some(overlay);
+            continue;
+        };
+        let Ok((_, _, mut overlay_camera, mut layers)) = overlays.get_mut(overlay) else {
+            continue;
+        };
+        if overlay_camera.target.normalize(primary_window) != target.normalize(primary_window) {
+            overlay_camera.target = target;
+        }
+        let same = match (&overlay_camera.viewport, &viewport) {
+            (Some(a), Some(b)) => same_viewport(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            overlay_camera.viewport = viewport;
+        }
+        if overlay_camera.order != camera.order + 2 {
+            overlay_camera.order = camera.order + 2;
+        }
+        let wanted = RenderLayers::layer(hires.layer);
+        if *layers != wanted {
+            *layers = wanted;
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use bevy::prelude::*;
+use bevy::render::camera::{Camera, ClearColorConfig, Projection};
+use bevy::render::view::RenderLayers;
+use bevy::sprite::Anchor;
+
+use crate::pixel_default::HelperCamera;
+use crate::{math, NoPixelSnap, PixelSafeArea, PixelViewport, PixelZoom};
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Cover the area outside of the target resolution of a `PixelZoom` camera
+/// with opaque sprites.
+///
+/// This is an alternative to `PixelViewport` for situations where the viewport
+/// of the camera can't be used (e.g. when another plugin manages
+/// `camera.viewport`). The bars are spawned as children of the camera, on the
+/// same render layers, just in front of the near plane.
+pub struct PixelLetterbox {
+    /// Color of the bars.
+    pub color: Color,
+}
+
+impl Default for PixelLetterbox {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// One of the four bars of a `PixelLetterbox` or `PixelLetterboxColor`.
+pub(crate) enum LetterboxBar {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn spawn_letterbox_bars(
+    mut commands: Commands,
+    cameras: Query<(Entity, &PixelLetterbox, Option<&RenderLayers>), Added<PixelLetterbox>>,
+) {
+    for (entity, letterbox, layers) in &cameras {
+        for bar in [
+            LetterboxBar::Left,
+            LetterboxBar::Right,
+            LetterboxBar::Top,
+            LetterboxBar::Bottom,
+        ] {
+            let mut bar = commands.spawn((
+                Name::new("Letterbox Bar"),
+                bar,
+                NoPixelSnap,
+                SpriteBundle {
+                    sprite: Sprite {
+                        anchor: Anchor::BottomLeft,
+                        color: letterbox.color,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ));
+            if let Some(layers) = layers {
+                bar.insert(*layers);
+            }
+            bar.set_parent(entity);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_letterbox_bars(
+    mut commands: Commands,
+    safe_area: Res<PixelSafeArea>,
+    cameras: Query<(
+        &Camera,
+        &PixelLetterbox,
+        &PixelZoom,
+        Option<&PixelViewport>,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+    overlays: Query<(), With<LetterboxOverlay>>,
+    mut bars: Query<(
+        Entity,
+        &LetterboxBar,
+        Option<&Parent>,
+        &mut Sprite,
+        &mut Transform,
+    )>,
+) {
+    for (entity, bar, parent, mut sprite, mut transform) in &mut bars {
+        if parent.is_some_and(|parent| overlays.contains(parent.get())) {
+            // Managed by `pixel_letterbox_color_system`.
+            continue;
+        }
+        let Some(camera) = parent.and_then(|parent| cameras.get(parent.get()).ok()) else {
+            // The camera is gone, or no longer has a `PixelLetterbox`.
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let (camera, letterbox, pixel_zoom, pixel_viewport, orthographic, projection) = camera;
+        let projection = match (orthographic, projection) {
+            (Some(orthographic), _) => orthographic,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic,
+            _ => continue,
+        };
+
+        if sprite.color != letterbox.color {
+            sprite.color = letterbox.color;
+        }
+
+        let Some((area, visible)) =
+            letterbox_rects(camera, pixel_zoom, pixel_viewport, &safe_area, projection)
+        else {
+            continue;
+        };
+
+        let (position, size) = match bar {
+            LetterboxBar::Left => (
+                area.min,
+                Vec2::new(visible.min.x - area.min.x, area.height()),
+            ),
+            LetterboxBar::Right => (
+                Vec2::new(visible.max.x, area.min.y),
+                Vec2::new(area.max.x - visible.max.x, area.height()),
+            ),
+            LetterboxBar::Top => (
+                Vec2::new(visible.min.x, visible.max.y),
+                Vec2::new(visible.width(), area.max.y - visible.max.y),
+            ),
+            LetterboxBar::Bottom => (
+                Vec2::new(visible.min.x, area.min.y),
+                Vec2::new(visible.width(), visible.min.y - area.min.y),
+            ),
+        };
+        let size = size.max(Vec2::ZERO);
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+        let translation = position.extend(-projection.near - 0.1);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
+
+/// Compute the area seen by the camera and the part of it covered by the
+/// target resolution, both in world units relative to the camera.
+fn letterbox_rects(
+    camera: &Camera,
+    pixel_zoom: &PixelZoom,
+    pixel_viewport: Option<&PixelViewport>,
+    safe_area: &PixelSafeArea,
+    projection: &OrthographicProjection,
+) -> Option<(Rect, Rect)> {
+    let logical_size = camera.logical_target_size()?;
+    let physical_size = camera.physical_target_size()?;
+    let camera_rect = camera.physical_viewport_rect()?.as_rect();
+    let area = projection.area;
+    if area.is_empty() || camera_rect.is_empty() {
+        return None;
+    }
+
+    let zoom = camera.logical_viewport_size()? / area.size();
+    let default_viewport = PixelViewport::default();
+    let target = math::viewport(
+        pixel_zoom,
+        pixel_viewport.unwrap_or(&default_viewport),
+        &safe_area.for_camera(camera),
+        zoom,
+        physical_size,
+        logical_size,
+    );
+    let target_rect = Rect::from_corners(
+        target.physical_position.as_vec2(),
+        (target.physical_position + target.physical_size).as_vec2(),
+    );
+
+    // Convert from physical pixels (origin at the top-left corner) to world
+    // units (origin at the camera, y pointing up).
+    let to_world = |point: Vec2| {
+        let t = (point - camera_rect.min) / camera_rect.size();
+        Vec2::new(
+            area.min.x + t.x * area.width(),
+            area.max.y - t.y * area.height(),
+        )
+    };
+    let visible = Rect::from_corners(to_world(target_rect.min), to_world(target_rect.max));
+    Some((area, visible))
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Paint the area outside of the viewport of a `PixelViewport` camera with a
+/// specific color.
+///
+/// Without this component, the letterbox bars show the clear color of the
+/// camera. With it, the bars are drawn by an additional camera (rendered just
+/// after this one, on the render layer `layer`), so the clear color of the
+/// camera is only visible inside the viewport.
+pub struct PixelLetterboxColor {
+    /// Color of the bars.
+    pub color: Color,
+    /// The render layer used to draw the bars.
+    pub layer: u8,
+    overlay: Option<Entity>,
+}
+
+impl PixelLetterboxColor {
+    /// Letterbox bars of the specified color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            layer: RenderLayers::TOTAL_LAYERS as u8 - 2,
+            overlay: None,
+        }
+    }
+
+    /// Draw the bars on the specified render layer.
+    pub fn with_layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+impl Default for PixelLetterboxColor {
+    fn default() -> Self {
+        Self::new(Color::BLACK)
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+/// Decorate the letterbox area of a `PixelLetterboxColor` camera with an
+/// image, scaled by the zoom of the camera.
+///
+/// - With `ImageScaleMode::Sliced`, the image is drawn as a frame around the
+///   viewport, whose borders extend outside of the viewport by the size of the
+///   slicer borders (in virtual pixels). The center of the image is drawn over
+///   the viewport, so it should be transparent.
+/// - With `ImageScaleMode::Tiled`, the image is tiled over the whole letterbox
+///   area.
+///
+/// The rest of the letterbox area uses the color of the `PixelLetterboxColor`.
+pub struct PixelLetterboxFrame {
+    /// The image of the frame.
+    pub image: Handle<Image>,
+    /// How the image is scaled to fill the frame.
+    pub scale_mode: ImageScaleMode,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The camera drawing the bars of a `PixelLetterboxColor`.
+pub(crate) struct LetterboxOverlay {
+    camera: Entity,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// The sprite drawing a sliced `PixelLetterboxFrame`.
+pub(crate) struct LetterboxFrameSprite;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_letterbox_color_system(
+    mut commands: Commands,
+    mut cameras: Query<(
+        Entity,
+        &Camera,
+        &mut PixelLetterboxColor,
+        Option<Ref<PixelLetterboxFrame>>,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+    overlays: Query<(Entity, &LetterboxOverlay)>,
+    mut bars: Query<(
+        Entity,
+        &LetterboxBar,
+        &Parent,
+        &mut Sprite,
+        &mut Transform,
+        &mut Handle<Image>,
+        Option<&ImageScaleMode>,
+    )>,
+    mut frames: Query<
+        (
+            Entity,
+            &Parent,
+            &mut Sprite,
+            &mut Transform,
+            &mut Handle<Image>,
+            &mut Visibility,
+            Option<&ImageScaleMode>,
+        ),
+        (With<LetterboxFrameSprite>, Without<LetterboxBar>),
+    >,
+) {
+    for (entity, overlay) in &overlays {
+        if !cameras.contains(overlay.camera) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for (entity, camera, mut letterbox, ..) in &mut cameras {
+        if letterbox.overlay.is_some() {
+            continue;
+        }
+        let layer = RenderLayers::layer(letterbox.layer);
+        let overlay = commands
+            .spawn((
+                Name::new("Letterbox Camera"),
+                HelperCamera,
+                LetterboxOverlay { camera: entity },
+                NoPixelSnap,
+                Camera2dBundle {
+                    camera: Camera {
+                        order: camera.order + 1,
+                        target: camera.target.clone(),
+                        clear_color: ClearColorConfig::None,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                layer,
+            ))
+            .id();
+        for bar in [
+            LetterboxBar::Left,
+            LetterboxBar::Right,
+            LetterboxBar::Top,
+            LetterboxBar::Bottom,
+        ] {
+            commands
+                .spawn((
+                    Name::new("Letterbox Bar"),
+                    bar,
+                    SpriteBundle {
+                        sprite: Sprite {
+                            anchor: Anchor::BottomLeft,
+                            color: letterbox.color,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .set_parent(overlay);
+        }
+        commands
+            .spawn((
+                Name::new("Letterbox Frame"),
+                LetterboxFrameSprite,
+                SpriteBundle {
+                    sprite: Sprite {
+                        anchor: Anchor::BottomLeft,
+                        ..Default::default()
+                    },
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                layer,
+            ))
+            .set_parent(overlay);
+        letterbox.overlay = Some(overlay);
+    }
+
+    for (entity, bar, parent, mut sprite, mut transform, mut image, scale_mode) in &mut bars {
+        let Ok((_, overlay)) = overlays.get(parent.get()) else {
+            continue;
+        };
+        let Ok((_, camera, letterbox, frame, orthographic, projection)) =
+            cameras.get(overlay.camera)
+        else {
+            continue;
+        };
+        let Some(logical_size) = camera.logical_target_size() else {
+            continue;
+        };
+        let Some(viewport) = logical_viewport_rect(camera) else {
+            set_rect(
+                &mut sprite,
+                &mut transform,
+                Rect::default(),
+                Vec2::ONE,
+                logical_size,
+                -0.1,
+            );
+            continue;
+        };
+
+        // The bars are textured by a tiled frame, and colored otherwise.
+        let tiled = frame.filter(|frame| matches!(frame.scale_mode, ImageScaleMode::Tiled { .. }));
+        let (color, texture) = match &tiled {
+            Some(frame) => (Color::WHITE, frame.image.clone()),
+            None => (letterbox.color, Handle::default()),
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+        if *image != texture {
+            *image = texture;
+        }
+        match &tiled {
+            Some(frame) if scale_mode.is_none() || frame.is_changed() => {
+                commands.entity(entity).insert(frame.scale_mode.clone());
+            }
+            None if scale_mode.is_some() => {
+                commands.entity(entity).remove::<ImageScaleMode>();
+            }
+            _ => (),
+        }
+
+        // The rectangle of the bar in logical pixels, with the origin at the
+        // top-left corner of the render target.
+        let (min, max) = (viewport.min, viewport.max);
+        let rect = match bar {
+            LetterboxBar::Left => Rect::new(0.0, 0.0, min.x, logical_size.y),
+            LetterboxBar::Right => Rect::new(max.x, 0.0, logical_size.x, logical_size.y),
+            LetterboxBar::Top => Rect::new(min.x, 0.0, max.x, min.y),
+            LetterboxBar::Bottom => Rect::new(min.x, max.y, max.x, logical_size.y),
+        };
+        let scale = match tiled {
+            Some(_) => virtual_pixel_size(camera, orthographic, projection),
+            None => Vec2::ONE,
+        };
+        set_rect(&mut sprite, &mut transform, rect, scale, logical_size, -0.1);
+    }
+
+    for (entity, parent, mut sprite, mut transform, mut image, mut visibility, scale_mode) in
+        &mut frames
+    {
+        let Ok((_, overlay)) = overlays.get(parent.get()) else {
+            continue;
+        };
+        let Ok((_, camera, _, frame, orthographic, projection)) = cameras.get(overlay.camera)
+        else {
+            continue;
+        };
+        let sliced = frame.filter(|frame| matches!(frame.scale_mode, ImageScaleMode::Sliced(_)));
+        let (Some(frame), Some(viewport), Some(logical_size)) = (
+            sliced,
+            logical_viewport_rect(camera),
+            camera.logical_target_size(),
+        ) else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+        if *visibility != Visibility::Inherited {
+            *visibility = Visibility::Inherited;
+        }
+        if *image != frame.image {
+            *image = frame.image.clone();
+        }
+        if scale_mode.is_none() || frame.is_changed() {
+            commands.entity(entity).insert(frame.scale_mode.clone());
+        }
+
+        let ImageScaleMode::Sliced(slicer) = &frame.scale_mode else {
+            continue;
+        };
+        let scale = virtual_pixel_size(camera, orthographic, projection);
+        let border = &slicer.border;
+        let rect = Rect::from_corners(
+            viewport.min - Vec2::new(border.left, border.top) * scale,
+            viewport.max + Vec2::new(border.right, border.bottom) * scale,
+        );
+        set_rect(
+            &mut sprite,
+            &mut transform,
+            rect,
+            scale,
+            logical_size,
+            -0.05,
+        );
+    }
+}
+
+/// The viewport of the camera in logical pixels, with the origin at the
+/// top-left corner of the render target.
+fn logical_viewport_rect(camera: &Camera) -> Option<Rect> {
+    let viewport = camera.viewport.as_ref()?;
+    let logical_size = camera.logical_target_size()?;
+    let physical_size = camera.physical_target_size()?;
+    let scale_factor = (physical_size.x as f32) / logical_size.x;
+    Some(Rect::from_corners(
+        viewport.physical_position.as_vec2() / scale_factor,
+        (viewport.physical_position + viewport.physical_size).as_vec2() / scale_factor,
+    ))
+}
+
+/// The size of a virtual pixel of the camera, in logical pixels.
+fn virtual_pixel_size(
+    camera: &Camera,
+    orthographic: Option<&OrthographicProjection>,
+    projection: Option<&Projection>,
+) -> Vec2 {
+    let area = match (orthographic, projection) {
+        (Some(orthographic), _) => orthographic.area,
+        (None, Some(Projection::Orthographic(orthographic))) => orthographic.area,
+        _ => return Vec2::ONE,
+    };
+    match camera.logical_viewport_size() {
+        Some(size) if !area.is_empty() => size / area.size(),
+        _ => Vec2::ONE,
+    }
+}
+
+/// Place a sprite of the letterbox overlay so that it covers `rect` (in
+/// logical pixels, with the origin at the top-left corner of the render
+/// target), with its image scaled by `scale`.
+fn set_rect(
+    sprite: &mut Sprite,
+    transform: &mut Transform,
+    rect: Rect,
+    scale: Vec2,
+    logical_size: Vec2,
+    z: f32,
+) {
+    let size = rect.size() / scale;
+    if sprite.custom_size != Some(size) {
+        sprite.custom_size = Some(size);
+    }
+    // The overlay camera uses the default 2D projection: one world unit per
+    // logical pixel, with the origin at the center.
+    let translation = Vec3::new(
+        rect.min.x - logical_size.x / 2.0,
+        logical_size.y / 2.0 - rect.max.y,
+        z,
+    );
+    let scale = scale.extend(1.0);
+    if transform.translation != translation || transform.scale != scale {
+        transform.translation = translation;
+        transform.scale = scale;
+    }
+}
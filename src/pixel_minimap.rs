@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+use bevy::render::texture::ImageSampler;
+
+use crate::pixel_default::HelperCamera;
+use crate::render_target::{render_target_image, resize_render_target};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Render a region of the world to the image targeted by a camera, at a fixed
+/// scale, e.g. for a minimap displayed in the UI.
+///
+/// The camera is centered on the region, and its image is resized to fit the
+/// whole region, each time the component changes. Use
+/// `PixelCameraCommands::spawn_minimap` to spawn the camera along with its
+/// image (with nearest sampling).
+pub struct PixelMinimap {
+    /// The area of the world shown by the minimap, in world units (i.e.
+    /// virtual pixels).
+    pub region: Rect,
+    /// The number of world units covered by each pixel of the image, in both
+    /// directions.
+    pub scale: u32,
+}
+
+impl PixelMinimap {
+    /// Show `region`, with one pixel of the image for `scale` x `scale` world
+    /// units.
+    pub fn new(region: Rect, scale: u32) -> Self {
+        Self { region, scale }
+    }
+
+    /// The size of the image, in pixels.
+    pub fn image_size(&self) -> UVec2 {
+        let scale = self.scale.max(1) as f32;
+        (self.region.size() / scale)
+            .ceil()
+            .as_uvec2()
+            .max(UVec2::ONE)
+    }
+}
+
+/// The components of a minimap camera rendering to `image`.
+pub(crate) fn minimap_camera(minimap: &PixelMinimap, image: Handle<Image>) -> impl Bundle {
+    (
+        Name::new("Minimap Camera"),
+        HelperCamera,
+        Camera2dBundle {
+            camera: Camera {
+                // Rendered before the other cameras, so that the image is up to
+                // date when it is displayed.
+                order: -1,
+                target: RenderTarget::Image(image),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        *minimap,
+    )
+}
+
+/// A render target image for `minimap`, with nearest sampling.
+pub(crate) fn minimap_image(minimap: &PixelMinimap) -> Image {
+    render_target_image(minimap.image_size(), ImageSampler::nearest())
+}
+
+pub(crate) fn pixel_minimap_system(
+    mut cameras: Query<
+        (
+            &Camera,
+            &PixelMinimap,
+            &mut OrthographicProjection,
+            &mut Transform,
+        ),
+        Changed<PixelMinimap>,
+    >,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (camera, minimap, mut projection, mut transform) in &mut cameras {
+        let size = minimap.image_size();
+        if let RenderTarget::Image(image) = &camera.target {
+            resize_render_target(&mut images, image, size);
+        }
+        let scale = minimap.scale.max(1) as f32;
+        projection.scaling_mode = ScalingMode::WindowSize(1.0 / scale);
+        // Align the edges of the image on whole world units.
+        let center = minimap.region.min + size.as_vec2() * scale / 2.0;
+        transform.translation = center.extend(transform.translation.z);
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{Material2d, Material2dPlugin, Mesh2dHandle};
+
+use crate::pixel_snap::virtual_pixel_size;
+use crate::{NoPixelSnap, PixelZoom};
+
+const PIXEL_OUTLINE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x4f86_d2b9_17e3_4c5a_9b0d_c6e1_38a7_52f0);
+
+/// Provides the outlines of sprites (see `PixelOutline`).
+pub struct PixelOutlinePlugin;
+
+impl Plugin for PixelOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PIXEL_OUTLINE_SHADER_HANDLE,
+            "pixel_outline.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(Material2dPlugin::<OutlineMaterial>::default())
+            .add_systems(PostUpdate, (pixel_outline_system, remove_outlines));
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Draw a one virtual pixel wide outline around the non-transparent texels of
+/// a sprite (e.g. for selection highlights).
+///
+/// The thickness follows the size of virtual pixels (i.e. the scale of the
+/// projection of the `PixelZoom` camera), whatever the size of the sprite and
+/// the scale of its `GlobalTransform`. The outline is drawn by a child entity,
+/// which is despawned when the component is removed. Only sprites using a
+/// whole image are supported (not texture atlases).
+///
+/// Requires the `PixelOutlinePlugin`.
+pub struct PixelOutline {
+    /// The color of the outline.
+    pub color: Color,
+    /// Where the outline is drawn.
+    pub mode: OutlineMode,
+}
+
+impl PixelOutline {
+    /// An outline of the specified color, outside the sprite.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            mode: OutlineMode::Outside,
+        }
+    }
+}
+
+impl Default for PixelOutline {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Where the outline of a `PixelOutline` is drawn.
+pub enum OutlineMode {
+    /// Around the sprite, on the transparent texels next to opaque ones.
+    #[default]
+    Outside,
+    /// Over the sprite, on the opaque texels next to transparent ones.
+    Inside,
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Debug, Clone, PartialEq)]
+/// The material used to draw a `PixelOutline`.
+///
+/// It is created and updated by the `PixelOutlinePlugin`.
+pub struct OutlineMaterial {
+    #[uniform(0)]
+    color: Vec4,
+    /// Thickness of the outline, in texels.
+    #[uniform(0)]
+    thickness: Vec2,
+    /// Margin around the image covered by the mesh, in texels.
+    #[uniform(0)]
+    margin: Vec2,
+    #[uniform(0)]
+    inside: u32,
+    #[texture(1)]
+    texture: Handle<Image>,
+}
+
+impl Material2d for OutlineMaterial {
+    fn fragment_shader() -> ShaderRef {
+        PIXEL_OUTLINE_SHADER_HANDLE.into()
+    }
+}
+
+/// The entity drawing the outline of a sprite.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct OutlineEntity(Entity);
+
+/// The size of the mesh of an outline entity.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct OutlineMeshSize(Vec2);
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn pixel_outline_system(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<OutlineMaterial>>,
+    cameras: Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+    sprites: Query<(
+        Entity,
+        &PixelOutline,
+        &Sprite,
+        &Handle<Image>,
+        Option<&GlobalTransform>,
+        Option<&OutlineEntity>,
+    )>,
+    mut outlines: Query<(
+        &mut Transform,
+        &Handle<OutlineMaterial>,
+        Option<&OutlineMeshSize>,
+    )>,
+) {
+    let pixel_size = virtual_pixel_size(&cameras);
+    for (entity, outline, sprite, image, global_transform, outline_entity) in &sprites {
+        let Some(image_size) = images.get(image).map(|image| image.size().as_vec2()) else {
+            continue;
+        };
+        let size = sprite.custom_size.unwrap_or(image_size);
+        if size.x <= 0.0 || size.y <= 0.0 {
+            continue;
+        }
+        // The scale of the sprite, including the one inherited from its parents.
+        let scale = global_transform.map_or(Vec2::ONE, |transform| {
+            transform.to_scale_rotation_translation().0.truncate().abs()
+        });
+        if scale.x <= 0.0 || scale.y <= 0.0 {
+            continue;
+        }
+        let thickness = pixel_size * image_size / (size * scale);
+        let margin = match outline.mode {
+            OutlineMode::Outside => thickness,
+            OutlineMode::Inside => Vec2::ZERO,
+        };
+        let updated = OutlineMaterial {
+            color: Vec4::from_array(outline.color.as_linear_rgba_f32()),
+            thickness,
+            margin,
+            inside: (outline.mode == OutlineMode::Inside).into(),
+            texture: image.clone(),
+        };
+        let mesh_size = size + 2.0 * margin * size / image_size;
+        // Drawn just over the sprite, with the same anchor and orientation.
+        let transform = Transform {
+            translation: (-sprite.anchor.as_vec() * size).extend(0.001),
+            scale: Vec3::new(
+                if sprite.flip_x { -1.0 } else { 1.0 },
+                if sprite.flip_y { -1.0 } else { 1.0 },
+                1.0,
+            ),
+            ..Default::default()
+        };
+
+        let Some((child, (mut current_transform, material, current_size))) = outline_entity
+            .and_then(|outline_entity| {
+                Some((outline_entity.0, outlines.get_mut(outline_entity.0).ok()?))
+            })
+        else {
+            let child = commands
+                .spawn((
+                    Name::new("Pixel Outline"),
+                    NoPixelSnap,
+                    Mesh2dHandle(meshes.add(Rectangle::from_size(mesh_size))),
+                    materials.add(updated),
+                    OutlineMeshSize(mesh_size),
+                    SpatialBundle::from_transform(transform),
+                ))
+                .id();
+            commands
+                .entity(entity)
+                .insert(OutlineEntity(child))
+                .add_child(child);
+            continue;
+        };
+
+        if *current_transform != transform {
+            *current_transform = transform;
+        }
+        if materials.get(material) != Some(&updated) {
+            if let Some(material) = materials.get_mut(material) {
+                *material = updated;
+            }
+        }
+        if current_size != Some(&OutlineMeshSize(mesh_size)) {
+            let mesh = meshes.add(Rectangle::from_size(mesh_size));
+            commands
+                .entity(child)
+                .insert((Mesh2dHandle(mesh), OutlineMeshSize(mesh_size)));
+        }
+    }
+}
+
+pub(crate) fn remove_outlines(
+    mut commands: Commands,
+    mut removed: RemovedComponents<PixelOutline>,
+    sprites: Query<&OutlineEntity, Without<PixelOutline>>,
+) {
+    for entity in removed.read() {
+        if let Ok(outline) = sprites.get(entity) {
+            commands.entity(outline.0).despawn_recursive();
+            commands.entity(entity).remove::<OutlineEntity>();
+        }
+    }
+}
@@ -0,0 +1,41 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct PixelOutline {
+    color: vec4<f32>,
+    // Thickness of the outline, in texels.
+    thickness: vec2<f32>,
+    // Margin around the image covered by the mesh, in texels.
+    margin: vec2<f32>,
+    inside: u32,
+};
+
+@group(2) @binding(0) var<uniform> outline: PixelOutline;
+@group(2) @binding(1) var sprite_texture: texture_2d<f32>;
+
+fn opaque(position: vec2<f32>) -> bool {
+    let size = vec2<f32>(textureDimensions(sprite_texture));
+    if any(position < vec2(0.0)) || any(position >= size) {
+        return false;
+    }
+    return textureLoad(sprite_texture, vec2<i32>(floor(position)), 0).a > 0.0;
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(sprite_texture));
+    let position = mesh.uv * (size + 2.0 * outline.margin) - outline.margin;
+    let center = opaque(position);
+    let right = opaque(position + vec2(outline.thickness.x, 0.0));
+    let left = opaque(position - vec2(outline.thickness.x, 0.0));
+    let down = opaque(position + vec2(0.0, outline.thickness.y));
+    let up = opaque(position - vec2(0.0, outline.thickness.y));
+
+    var on_outline = !center && (right || left || down || up);
+    if outline.inside != 0u {
+        on_outline = center && !(right && left && down && up);
+    }
+    if !on_outline {
+        discard;
+    }
+    return outline.color;
+}
@@ -0,0 +1,153 @@
+use bevy::asset::load_internal_asset;
+use bevy::core_pipeline::core_2d::graph::Core2d;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::extract_component::{ExtractComponent, ExtractComponentPlugin};
+use bevy::render::render_graph::{RenderGraph, RenderGraphApp, RenderLabel};
+use bevy::render::RenderApp;
+
+use crate::pixel_dither::DitherLabel;
+use crate::post_process::{
+    physical_pixels_per_unit, PostProcess, PostProcessPlugin, PostProcessTexture,
+};
+
+const PIXEL_PALETTE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x9b0e_2f47_c5d1_4a36_8e7c_1d92_f40b_63a8);
+
+/// Provides the palette post-processing pass (see `PixelPalette`).
+///
+/// With the `PixelDitherPlugin`, the palette pass runs after the dithering
+/// pass, so that the final frame only contains colors of the palette.
+pub struct PixelPalettePlugin;
+
+impl Plugin for PixelPalettePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PIXEL_PALETTE_SHADER_HANDLE,
+            "pixel_palette.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins((
+            ExtractComponentPlugin::<PixelPalette>::default(),
+            PostProcessPlugin::<PaletteUniform>::default(),
+        ));
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        // Both nodes have been added once all the plugins are built.
+        let dither = render_app
+            .world
+            .resource::<RenderGraph>()
+            .get_sub_graph(Core2d)
+            .is_some_and(|graph| graph.get_node_state(DitherLabel).is_ok());
+        if dither {
+            render_app.add_render_graph_edge(Core2d, DitherLabel, PaletteLabel);
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Remap the colors rendered by a 2D camera to a fixed palette.
+///
+/// The palette is supplied as a small image (the LUT), each texel being one
+/// color of the palette; every pixel of the frame is replaced by the closest
+/// color of the LUT. The image should use a format that can be loaded in a
+/// shader (e.g. `Rgba8UnormSrgb`), and be small: each pixel of the frame is
+/// compared to every texel.
+///
+/// This is a post-processing pass, applied after tonemapping. On a
+/// `PixelCanvas` camera, it runs on the canvas, i.e. once per virtual pixel.
+///
+/// Requires the `PixelPalettePlugin`.
+pub struct PixelPalette {
+    /// The palette image.
+    pub lut: Handle<Image>,
+    /// How colors are matched to the palette.
+    pub matching: PaletteMatching,
+}
+
+impl PixelPalette {
+    /// Remap to the colors of `lut`, with nearest-color matching.
+    pub fn new(lut: Handle<Image>) -> Self {
+        Self {
+            lut,
+            matching: PaletteMatching::Nearest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// How the colors of the frame are matched to a `PixelPalette`.
+pub enum PaletteMatching {
+    /// Replace each pixel by the closest color of the palette.
+    #[default]
+    Nearest,
+    /// Offset each pixel by a 4x4 Bayer matrix, aligned on the virtual pixel
+    /// grid, before replacing it by the closest color. Gradients are then
+    /// rendered with a dithered pattern instead of bands.
+    OrderedDither {
+        /// Amplitude of the offset (e.g. the typical distance between two
+        /// colors of the palette).
+        spread: f32,
+    },
+}
+
+// The `ShaderType` derive generates items that trigger the `dead_code` lint.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::prelude::*;
+    use bevy::render::render_resource::ShaderType;
+
+    /// The uniform extracted from a `PixelPalette`.
+    #[doc(hidden)]
+    #[derive(Component, ShaderType, Clone)]
+    pub struct PaletteUniform {
+        pub(crate) pixel_size: Vec2,
+        pub(crate) origin: Vec2,
+        pub(crate) dither_spread: f32,
+    }
+}
+use uniform::PaletteUniform;
+
+impl ExtractComponent for PixelPalette {
+    type QueryData = (
+        &'static Self,
+        &'static Camera,
+        Option<&'static OrthographicProjection>,
+        Option<&'static Projection>,
+    );
+    type QueryFilter = ();
+    type Out = (PostProcessTexture<PaletteUniform>, PaletteUniform);
+
+    fn extract_component(
+        (palette, camera, orthographic, projection): QueryItem<Self::QueryData>,
+    ) -> Option<Self::Out> {
+        let viewport = camera.physical_viewport_rect()?;
+        Some((
+            PostProcessTexture::new(palette.lut.clone()),
+            PaletteUniform {
+                pixel_size: physical_pixels_per_unit(camera, orthographic, projection),
+                origin: viewport.min.as_vec2(),
+                dither_spread: match palette.matching {
+                    PaletteMatching::Nearest => 0.0,
+                    PaletteMatching::OrderedDither { spread } => spread,
+                },
+            },
+        ))
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Default, RenderLabel)]
+pub(crate) struct PaletteLabel;
+
+impl PostProcess for PaletteUniform {
+    type Label = PaletteLabel;
+    const NAME: &'static str = "pixel_palette";
+    const SHADER: Handle<Shader> = PIXEL_PALETTE_SHADER_HANDLE;
+    const TEXTURE: bool = true;
+}
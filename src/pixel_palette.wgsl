@@ -0,0 +1,47 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct PixelPalette {
+    // Size of a virtual pixel, in physical pixels.
+    pixel_size: vec2<f32>,
+    // Top-left corner of the viewport, in physical pixels.
+    origin: vec2<f32>,
+    // Amplitude of the ordered dithering (zero for nearest color matching).
+    dither_spread: f32,
+};
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var screen_sampler: sampler;
+@group(0) @binding(2) var palette_texture: texture_2d<f32>;
+@group(0) @binding(3) var<uniform> palette: PixelPalette;
+
+// Threshold of the 4x4 Bayer matrix, between -0.5 and 0.5.
+fn bayer4(cell: vec2<u32>) -> f32 {
+    let x = cell.x % 4u;
+    let y = cell.y % 4u;
+    let a = x ^ y;
+    let index = ((a & 1u) << 3u) | ((y & 1u) << 2u) | (a & 2u) | ((y & 2u) >> 1u);
+    return (f32(index) + 0.5) / 16.0 - 0.5;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(screen_texture, screen_sampler, in.uv);
+    let cell = vec2<u32>(max(floor((in.position.xy - palette.origin) / palette.pixel_size), vec2(0.0)));
+    let target_color = color.rgb + bayer4(cell) * palette.dither_spread;
+
+    let size = textureDimensions(palette_texture);
+    var best = vec3(0.0);
+    var best_distance = 1e30;
+    for (var y = 0u; y < size.y; y++) {
+        for (var x = 0u; x < size.x; x++) {
+            let candidate = textureLoad(palette_texture, vec2(x, y), 0).rgb;
+            let delta = candidate - target_color;
+            let distance = dot(delta, delta);
+            if distance < best_distance {
+                best = candidate;
+                best_distance = distance;
+            }
+        }
+    }
+    return vec4(best, color.a);
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::math;
+use crate::pixel_cursor::virtual_pixel_scale;
+use crate::PixelZoom;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Move a background (or foreground) layer relative to a `PixelZoom` camera,
+/// for a parallax effect.
+///
+/// The `Transform` of the layer is overwritten in `PostUpdate`, after the
+/// camera has been moved and snapped: the layer is placed at `origin` when
+/// the camera is at the origin of the world, and scrolls by `factor` times
+/// the movement of the world (so `1.0` scrolls like the world, `0.0` stays
+/// fixed on screen, and values in between are farther away). The layer should
+/// not be the child of a moving entity.
+///
+/// With `snap`, the layer is aligned on whole virtual pixels, so that it
+/// doesn't shimmer when the camera scrolls slowly (each layer then moves by
+/// whole pixels, at its own pace). With `wrap_width`, the layer jumps by
+/// multiples of this width (in world units) to stay around the camera: it
+/// should be made of horizontally repeating tiles, covering the visible area
+/// plus one tile.
+pub struct ParallaxLayer {
+    /// The scrolling speed of the layer, relative to the world, on each axis.
+    pub factor: Vec2,
+    /// The position of the layer when the camera is at the origin.
+    pub origin: Vec2,
+    /// Align the layer on whole virtual pixels.
+    pub snap: bool,
+    /// The width of the repeating pattern of the layer, if it wraps
+    /// horizontally.
+    pub wrap_width: Option<f32>,
+    /// The camera used for the parallax, or the first `PixelZoom` camera if
+    /// `None`.
+    pub camera: Option<Entity>,
+}
+
+impl ParallaxLayer {
+    /// A layer scrolling at `factor` times the speed of the world, on both
+    /// axes.
+    pub fn new(factor: f32) -> Self {
+        Self {
+            factor: Vec2::splat(factor),
+            origin: Vec2::ZERO,
+            snap: true,
+            wrap_width: None,
+            camera: None,
+        }
+    }
+
+    /// Place the layer at `origin` when the camera is at the origin of the
+    /// world.
+    pub fn with_origin(self, origin: Vec2) -> Self {
+        Self { origin, ..self }
+    }
+
+    /// Allow the layer to move by fractions of virtual pixels.
+    pub fn without_snap(self) -> Self {
+        Self {
+            snap: false,
+            ..self
+        }
+    }
+
+    /// Wrap the layer horizontally, with a pattern of `width` world units.
+    pub fn with_wrap(self, width: f32) -> Self {
+        Self {
+            wrap_width: Some(width),
+            ..self
+        }
+    }
+
+    /// Use a specific camera for the parallax.
+    pub fn with_camera(self, camera: Entity) -> Self {
+        Self {
+            camera: Some(camera),
+            ..self
+        }
+    }
+
+    /// The position of the layer for a camera at `camera`, with virtual
+    /// pixels of `pixel_size` world units.
+    fn position(&self, camera: Vec2, pixel_size: f32) -> Vec2 {
+        let mut position = self.origin + camera * (Vec2::ONE - self.factor);
+        if let Some(width) = self.wrap_width.filter(|width| *width > 0.0) {
+            position.x -= ((position.x - camera.x) / width).round() * width;
+        }
+        if self.snap {
+            position = math::snap_to_grid(position, Vec2::splat(pixel_size));
+        }
+        position
+    }
+}
+
+impl Default for ParallaxLayer {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn parallax_layer_system(
+    cameras: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+        ),
+        With<PixelZoom>,
+    >,
+    mut layers: Query<(&ParallaxLayer, &mut Transform), Without<PixelZoom>>,
+) {
+    for (layer, mut transform) in &mut layers {
+        let camera = match layer.camera {
+            Some(camera) => cameras.get(camera).ok(),
+            None => cameras.iter().next(),
+        };
+        let Some((_, camera_transform, orthographic, projection)) = camera else {
+            continue;
+        };
+        let pixel_size = virtual_pixel_scale(orthographic, projection).unwrap_or(1.0);
+        let position = layer.position(camera_transform.translation.truncate(), pixel_size);
+        let translation = position.extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    }
+}
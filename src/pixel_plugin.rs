@@ -1,17 +1,142 @@
+#[cfg(feature = "legacy")]
 #[allow(deprecated)]
 use super::PixelProjection;
 
-use bevy::prelude::{App, IntoSystemConfigs, Plugin, PostUpdate};
-use bevy::render::camera::{
-    self, Camera, OrthographicProjection, PerspectiveProjection, Projection, ScalingMode,
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::prelude::{
+    App, IntoSystemConfigs, IntoSystemSetConfigs, Plugin, PostUpdate, PreUpdate, Res, Resource,
+    SystemSet,
 };
+use bevy::render::camera::{self, Camera, OrthographicProjection, Projection, ScalingMode};
 use bevy::render::primitives::Aabb;
-use bevy::render::view::visibility;
+use bevy::render::texture::{ImageFilterMode, ImagePlugin};
+use bevy::render::view::{visibility, Msaa};
 use bevy::render::view::{InheritedVisibility, Visibility, VisibleEntities};
 use bevy::transform::TransformSystem;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// Provides the camera system.
-pub struct PixelCameraPlugin;
+///
+/// `PixelCameraPlugin::default()` registers everything; the optional parts
+/// can be disabled if they are not used.
+pub struct PixelCameraPlugin {
+    /// Register the systems of the deprecated `PixelProjection`.
+    #[cfg(feature = "legacy")]
+    pub enable_legacy_projection: bool,
+    /// Disable multisample anti-aliasing (by inserting `Msaa::Off`), which
+    /// smooths the edges of rotated and scaled sprites.
+    pub auto_msaa_off: bool,
+    /// Register the systems of `PixelCameraFollow`, `PixelCameraBounds`,
+    /// `PixelShake`, `PixelZoomPunch`, `ParallaxLayer` and `CinematicBars`.
+    pub camera_effects: bool,
+    /// Check the samplers of the sprite images (see `PixelSamplerCheck`).
+    pub sampler_check: bool,
+    /// Register `VirtualCursor` and `VirtualTouches`, and their systems.
+    /// The `PixelCursorPlugin` registers them anyway.
+    pub virtual_cursor: bool,
+    /// Register the systems of `PixelSnap` (including
+    /// `SnapMode::GlobalTransform`), `ImplicitPixelSnap`, `PixelSnapText`,
+    /// `SnapCameraToPixelGrid` and `QuantizedRotation` (also needed by
+    /// `PixelCanvas::smooth_scroll`).
+    pub snapping: bool,
+    /// Register the systems of the letterbox bars (see `PixelViewport` and
+    /// `PixelLetterboxColor`).
+    pub letterbox: bool,
+    /// Register the systems of `PixelHiResOverlay`, `PixelBackground` and
+    /// `PixelMinimap`.
+    pub overlays: bool,
+    /// Register the system of `PixelSplitScreen`.
+    pub split_screen: bool,
+    /// Register the system of `PixelSupersample`.
+    pub supersample: bool,
+    /// Warn about the sprites that are not aligned on virtual pixels (see
+    /// `PixelAlignmentWarnings`).
+    pub alignment_warnings: bool,
+    /// The schedule of the systems computing the zoom and updating the
+    /// viewports (`PixelCameraSet::ComputeZoom` and
+    /// `PixelCameraSet::ApplyViewport`), `PostUpdate` by default.
+    ///
+    /// In another schedule, these sets are still ordered relative to each
+    /// other, but not relative to the transform propagation or Bevy's update
+    /// of the projections: order them relative to your own systems instead.
+    /// The other systems of the plugin stay in `PreUpdate` and `PostUpdate`.
+    pub schedule: InternedScheduleLabel,
+}
+
+impl Default for PixelCameraPlugin {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "legacy")]
+            enable_legacy_projection: true,
+            auto_msaa_off: false,
+            camera_effects: true,
+            sampler_check: true,
+            virtual_cursor: true,
+            snapping: true,
+            letterbox: true,
+            overlays: true,
+            split_screen: true,
+            supersample: true,
+            alignment_warnings: true,
+            schedule: PostUpdate.intern(),
+        }
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The system sets of `PixelCameraPlugin`, to order other systems relative
+/// to the plugin.
+///
+/// Except for `Restore` (in `PreUpdate`), they all run in `PostUpdate`, in
+/// this order: `ComputeZoom`, `MoveCamera`, `SnapCamera` and `Snap` run before
+/// the transforms are propagated, `ApplyViewport` after `ComputeZoom`, and
+/// `SnapGlobal` after the propagation. For example, a custom camera follow
+/// system should run in `Update`, or in `PostUpdate` after `ComputeZoom` and
+/// before `SnapCamera`.
+pub enum PixelCameraSet {
+    /// Compute the zoom of the `PixelZoom` cameras, and update their scaling
+    /// mode and viewport (including `PixelZoomTransition` and
+    /// `PixelCameraInfo`).
+    ComputeZoom,
+    /// Update the letterbox and cinematic bars, for the new viewport.
+    ApplyViewport,
+    /// Move the cameras (`PixelCameraFollow`, `PixelCameraBounds`,
+    /// `PixelShake` and `PixelZoomPunch`).
+    MoveCamera,
+    /// Snap the cameras to the virtual pixel grid (`SnapCameraToPixelGrid`).
+    SnapCamera,
+    /// Snap the `Transform` of entities (`PixelSnap`, `QuantizedRotation`),
+    /// and place the `ParallaxLayer`s.
+    Snap,
+    /// Snap the `GlobalTransform` of entities, after propagation.
+    SnapGlobal,
+    /// Restore the smooth positions and rotations of the previous frame, in
+    /// `PreUpdate`.
+    Restore,
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+/// Suspend the systems of the plugin while `false`, e.g. to give full control
+/// of the cameras to an editor.
+///
+/// While suspended, the projections, viewports and transforms of the cameras
+/// are left untouched (including by `PixelCanvas`, `PixelCameraFollow`,
+/// `SnapCameraToPixelGrid`, etc.). The offsets applied by the snapping are
+/// still restored. The zoom and viewports are recomputed when the plugin is
+/// enabled again.
+pub struct PixelCameraEnabled(pub bool);
+
+impl Default for PixelCameraEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Run condition of the systems suspended by `PixelCameraEnabled`.
+#[allow(clippy::unnecessary_map_or)]
+pub(crate) fn pixel_camera_enabled(enabled: Option<Res<PixelCameraEnabled>>) -> bool {
+    enabled.map_or(true, |enabled| enabled.0)
+}
 
 #[allow(deprecated)]
 impl Plugin for PixelCameraPlugin {
@@ -23,21 +148,254 @@ impl Plugin for PixelCameraPlugin {
             .register_type::<VisibleEntities>()
             .register_type::<ScalingMode>()
             .register_type::<Aabb>()
-            .add_systems(PostUpdate, super::update_pixel_camera_viewport)
-            .add_systems(PostUpdate, camera::camera_system::<PixelProjection>)
-            .add_systems(
+            .init_resource::<PixelCameraEnabled>()
+            .init_resource::<super::PixelZoomOverride>()
+            .init_resource::<super::PixelSafeArea>()
+            .add_event::<super::PixelZoomChanged>()
+            .add_event::<super::PixelTargetTooLarge>()
+            .configure_sets(
+                PostUpdate,
+                (
+                    PixelCameraSet::ComputeZoom,
+                    PixelCameraSet::MoveCamera,
+                    PixelCameraSet::SnapCamera,
+                    PixelCameraSet::Snap,
+                )
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .configure_sets(
+                PostUpdate,
+                PixelCameraSet::ApplyViewport
+                    .after(PixelCameraSet::ComputeZoom)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .configure_sets(
                 PostUpdate,
-                visibility::update_frusta::<PixelProjection>
-                    .in_set(visibility::VisibilitySystems::UpdateOrthographicFrusta)
-                    .after(camera::camera_system::<PixelProjection>)
+                PixelCameraSet::SnapGlobal
                     .after(TransformSystem::TransformPropagate)
-                    .ambiguous_with(visibility::update_frusta::<PerspectiveProjection>)
-                    .ambiguous_with(visibility::update_frusta::<OrthographicProjection>)
-                    .ambiguous_with(visibility::update_frusta::<Projection>),
+                    .before(visibility::VisibilitySystems::CheckVisibility),
+            )
+            .configure_sets(
+                self.schedule,
+                PixelCameraSet::ApplyViewport.after(PixelCameraSet::ComputeZoom),
+            )
+            .configure_sets(
+                self.schedule,
+                (PixelCameraSet::ComputeZoom, PixelCameraSet::ApplyViewport)
+                    .run_if(pixel_camera_enabled),
+            )
+            .configure_sets(
+                PostUpdate,
+                (
+                    PixelCameraSet::MoveCamera,
+                    PixelCameraSet::SnapCamera,
+                    PixelCameraSet::Snap,
+                    PixelCameraSet::SnapGlobal,
+                )
+                    .run_if(pixel_camera_enabled),
             )
             .add_systems(
+                self.schedule,
+                super::default_pixel_zoom_system
+                    .before(PixelCameraSet::ComputeZoom)
+                    .run_if(pixel_camera_enabled),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    super::pixel_zoom_system
+                        .after(camera::camera_system::<OrthographicProjection>)
+                        .after(camera::camera_system::<Projection>),
+                    super::pixel_zoom_transition_system,
+                    super::pixel_camera_info_system,
+                )
+                    .chain()
+                    .in_set(PixelCameraSet::ComputeZoom),
+            );
+
+        if self.supersample {
+            app.add_systems(
+                self.schedule,
+                super::pixel_supersample_system
+                    .before(super::pixel_zoom_system)
+                    .in_set(PixelCameraSet::ComputeZoom),
+            );
+        }
+
+        if self.split_screen {
+            app.add_systems(
+                self.schedule,
+                super::split_screen_zoom_system
+                    .after(super::pixel_zoom_transition_system)
+                    .before(super::pixel_camera_info_system)
+                    .in_set(PixelCameraSet::ComputeZoom),
+            );
+        }
+
+        if self.letterbox {
+            app.add_systems(
+                self.schedule,
+                (
+                    super::spawn_letterbox_bars,
+                    super::update_letterbox_bars,
+                    super::pixel_letterbox_color_system,
+                )
+                    .chain()
+                    .in_set(PixelCameraSet::ApplyViewport),
+            );
+        }
+
+        if self.overlays {
+            app.add_systems(
+                self.schedule,
+                (
+                    super::pixel_hires_overlay_system,
+                    super::pixel_background_system,
+                )
+                    .chain()
+                    .after(super::pixel_letterbox_color_system)
+                    .in_set(PixelCameraSet::ApplyViewport),
+            )
+            .add_systems(
+                PostUpdate,
+                super::pixel_minimap_system
+                    .before(camera::camera_system::<OrthographicProjection>)
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(pixel_camera_enabled),
+            );
+        }
+
+        if self.snapping {
+            app.init_resource::<super::ImplicitPixelSnap>()
+                .add_systems(
+                    PostUpdate,
+                    super::snap_camera_system.in_set(PixelCameraSet::SnapCamera),
+                )
+                .add_systems(
+                    PostUpdate,
+                    (super::pixel_snap_system, super::quantize_rotation_system)
+                        .in_set(PixelCameraSet::Snap),
+                )
+                .add_systems(
+                    PostUpdate,
+                    super::pixel_snap_global_system.in_set(PixelCameraSet::SnapGlobal),
+                )
+                .add_systems(
+                    PreUpdate,
+                    (
+                        super::restore_subpixel_offsets,
+                        super::restore_camera_offsets,
+                        super::restore_global_snap_offsets,
+                        super::restore_smooth_rotations,
+                    )
+                        .in_set(PixelCameraSet::Restore),
+                );
+        }
+
+        if self.alignment_warnings {
+            app.add_systems(
+                PostUpdate,
+                super::pixel_alignment_warnings_system
+                    .after(visibility::VisibilitySystems::CheckVisibility),
+            );
+        }
+
+        if self.virtual_cursor {
+            super::pixel_cursor::add_virtual_cursor(app);
+        }
+
+        #[cfg(feature = "legacy")]
+        if self.enable_legacy_projection {
+            app.add_systems(PostUpdate, super::update_pixel_camera_viewport)
+                .add_systems(PostUpdate, camera::camera_system::<PixelProjection>)
+                .add_systems(
+                    PostUpdate,
+                    visibility::update_frusta::<PixelProjection>
+                        .in_set(visibility::VisibilitySystems::UpdateOrthographicFrusta)
+                        .after(camera::camera_system::<PixelProjection>)
+                        .after(TransformSystem::TransformPropagate)
+                        .ambiguous_with(visibility::update_frusta::<camera::PerspectiveProjection>)
+                        .ambiguous_with(visibility::update_frusta::<OrthographicProjection>)
+                        .ambiguous_with(visibility::update_frusta::<Projection>),
+                );
+        }
+
+        if self.auto_msaa_off {
+            app.insert_resource(Msaa::Off);
+        }
+
+        if self.camera_effects {
+            app.add_systems(
+                self.schedule,
+                (super::spawn_cinematic_bars, super::update_cinematic_bars)
+                    .chain()
+                    .in_set(PixelCameraSet::ApplyViewport),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    super::pixel_camera_follow_system,
+                    super::pixel_camera_bounds_system,
+                    super::pixel_shake_system,
+                    super::pixel_zoom_punch_system,
+                )
+                    .chain()
+                    .in_set(PixelCameraSet::MoveCamera),
+            )
+            .add_systems(
+                PostUpdate,
+                super::parallax_layer_system
+                    .before(super::pixel_snap_system)
+                    .in_set(PixelCameraSet::Snap),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    super::restore_shake_offsets,
+                    super::restore_zoom_punch_scales,
+                )
+                    .in_set(PixelCameraSet::Restore),
+            );
+        }
+
+        #[cfg(feature = "recording")]
+        app.add_systems(
+            PostUpdate,
+            super::pixel_recorder::pixel_recorder_system.after(PixelCameraSet::ComputeZoom),
+        );
+
+        let linear_sampling =
+            app.get_added_plugins::<ImagePlugin>()
+                .first()
+                .is_some_and(|plugin| {
+                    matches!(plugin.default_sampler.mag_filter, ImageFilterMode::Linear)
+                });
+        if self.sampler_check {
+            app.insert_resource(super::PixelSamplerCheck::new(linear_sampling))
+                .add_systems(PostUpdate, super::pixel_sampler_check_system);
+        }
+
+        #[cfg(feature = "text")]
+        {
+            if linear_sampling {
+                app.insert_resource(super::pixel_snap_text::LinearTextSampling);
+            }
+            app.add_systems(
                 PostUpdate,
-                super::pixel_zoom_system.after(camera::camera_system::<OrthographicProjection>),
+                (
+                    super::pixel_snap_text::snap_text_glyphs
+                        .after(bevy::text::update_text2d_layout),
+                    super::pixel_snap_text::warn_linear_text_sampling,
+                ),
             );
+            if self.snapping {
+                app.add_systems(
+                    PostUpdate,
+                    super::pixel_snap_text::pixel_snap_text_system
+                        .in_set(PixelCameraSet::SnapGlobal),
+                );
+            }
+        }
     }
 }
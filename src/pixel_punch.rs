@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::PixelZoom;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Briefly zoom a `PixelZoom` camera in, e.g. on hits or explosions, then
+/// settle back on its exact zoom.
+///
+/// The camera zooms in by `strength` (e.g. `0.1` for 10%) at once, and eases
+/// back over `duration` seconds. Instead of the scaling mode (which is managed
+/// by the zoom system), the effect scales the `Transform` of the camera,
+/// which is restored at the beginning of each frame. The camera is not
+/// pixel-perfect during the effect. The component removes itself once the
+/// effect is over (see also `PixelCameraCommands::zoom_punch`).
+pub struct PixelZoomPunch {
+    /// The fractional zoom added at the start of the effect.
+    pub strength: f32,
+    /// The duration of the effect, in seconds.
+    pub duration: f32,
+    elapsed: f32,
+    pub(crate) scale: f32,
+}
+
+impl PixelZoomPunch {
+    /// A punch of `strength`, lasting `duration` seconds.
+    pub fn new(strength: f32, duration: f32) -> Self {
+        Self {
+            strength,
+            duration,
+            elapsed: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Default for PixelZoomPunch {
+    fn default() -> Self {
+        Self::new(0.1, 0.2)
+    }
+}
+
+pub(crate) fn pixel_zoom_punch_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(Entity, &mut PixelZoomPunch, &mut Transform), With<PixelZoom>>,
+) {
+    for (entity, mut punch, mut transform) in &mut cameras {
+        if punch.elapsed >= punch.duration {
+            commands.entity(entity).remove::<PixelZoomPunch>();
+            continue;
+        }
+        let t = punch.elapsed / punch.duration;
+        punch.elapsed += time.delta_seconds();
+        let envelope = (1.0 - t) * (1.0 - t);
+        // Scaling the camera transform down zooms in.
+        let scale = 1.0 / (1.0 + punch.strength * envelope).max(0.01);
+        punch.scale = scale;
+        transform.scale.x *= scale;
+        transform.scale.y *= scale;
+    }
+}
+
+pub(crate) fn restore_zoom_punch_scales(mut cameras: Query<(&mut Transform, &mut PixelZoomPunch)>) {
+    for (mut transform, mut punch) in &mut cameras {
+        if punch.scale != 1.0 {
+            transform.scale.x /= punch.scale;
+            transform.scale.y /= punch.scale;
+            punch.scale = 1.0;
+        }
+    }
+}
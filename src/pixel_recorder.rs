@@ -0,0 +1,201 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::render::camera::{NormalizedRenderTarget, Projection};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension};
+use bevy::render::texture::TextureFormatPixelInfo;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+
+use crate::post_process::physical_pixels_per_unit;
+use crate::PixelCanvas;
+
+#[derive(Component, Debug, Clone)]
+/// Record the frames rendered by a `PixelZoom` camera, at the virtual
+/// resolution, into a sequence of PNG images.
+///
+/// Each frame is captured with a screenshot of the window, and downsampled to
+/// one pixel per virtual pixel before being saved (as `frame_00000.png`,
+/// `frame_00001.png`, ...) in `directory`. The component removes itself once
+/// `duration` has elapsed.
+///
+/// Only available with the `recording` feature.
+pub struct PixelRecorder {
+    /// The directory where the frames are saved (created if needed).
+    pub directory: PathBuf,
+    /// The length of the recording, in seconds.
+    pub duration: f32,
+    elapsed: f32,
+    frame: u32,
+}
+
+impl PixelRecorder {
+    /// Record `duration` seconds of frames into `directory`.
+    pub fn new(directory: impl Into<PathBuf>, duration: f32) -> Self {
+        Self {
+            directory: directory.into(),
+            duration,
+            elapsed: 0.0,
+            frame: 0,
+        }
+    }
+
+    /// The number of frames captured so far.
+    pub fn frames(&self) -> u32 {
+        self.frame
+    }
+}
+
+/// The part of a screenshot covered by the virtual pixels of a camera.
+#[derive(Debug, Clone, Copy)]
+struct CaptureArea {
+    /// Top-left corner, in physical pixels.
+    origin: Vec2,
+    /// Size of a virtual pixel, in physical pixels.
+    pixel_size: Vec2,
+    /// Size of the captured frame, in virtual pixels.
+    size: UVec2,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_recorder_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    screenshots: Option<ResMut<ScreenshotManager>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    mut cameras: Query<(
+        Entity,
+        &Camera,
+        &mut PixelRecorder,
+        Option<&PixelCanvas>,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+) {
+    let Some(mut screenshots) = screenshots else {
+        return;
+    };
+    let primary_window = primary_window.iter().next();
+
+    for (entity, camera, mut recorder, canvas, orthographic, projection) in &mut cameras {
+        if recorder.elapsed >= recorder.duration {
+            info!(
+                "Recorded {} frames to {}",
+                recorder.frame,
+                recorder.directory.display()
+            );
+            commands.entity(entity).remove::<PixelRecorder>();
+            continue;
+        }
+        if recorder.frame == 0 && recorder.elapsed == 0.0 {
+            if let Err(error) = std::fs::create_dir_all(&recorder.directory) {
+                error!(
+                    "Cannot create recording directory {}: {error}",
+                    recorder.directory.display()
+                );
+                commands.entity(entity).remove::<PixelRecorder>();
+                continue;
+            }
+        }
+        recorder.elapsed += time.delta_seconds();
+
+        let capture = match canvas {
+            Some(canvas) => canvas.display().and_then(|(window_entity, size, zoom)| {
+                let window = windows.get(window_entity).ok()?;
+                let pixel_size = zoom * window.scale_factor();
+                let window_size = Vec2::new(
+                    window.physical_width() as f32,
+                    window.physical_height() as f32,
+                );
+                let origin = ((window_size - size.as_vec2() * pixel_size) / 2.0).round();
+                Some((
+                    window_entity,
+                    CaptureArea {
+                        origin,
+                        pixel_size,
+                        size,
+                    },
+                ))
+            }),
+            None => {
+                let window = match camera.target.normalize(primary_window) {
+                    Some(NormalizedRenderTarget::Window(window_ref)) => window_ref.entity(),
+                    _ => continue,
+                };
+                camera.physical_viewport_rect().map(|viewport| {
+                    let pixel_size = physical_pixels_per_unit(camera, orthographic, projection);
+                    let size = (viewport.size().as_vec2() / pixel_size).floor().as_uvec2();
+                    (
+                        window,
+                        CaptureArea {
+                            origin: viewport.min.as_vec2(),
+                            pixel_size,
+                            size,
+                        },
+                    )
+                })
+            }
+        };
+        let Some((window, area)) = capture else {
+            continue;
+        };
+        if area.size.x == 0 || area.size.y == 0 {
+            continue;
+        }
+
+        let path = recorder
+            .directory
+            .join(format!("frame_{:05}.png", recorder.frame));
+        let requested = screenshots.take_screenshot(window, move |screenshot| {
+            let Some(frame) = downsample(&screenshot, area) else {
+                error!("Cannot record frame: unsupported screenshot format");
+                return;
+            };
+            match frame.try_into_dynamic() {
+                Ok(frame) => {
+                    if let Err(error) = frame.to_rgb8().save(&path) {
+                        error!("Cannot save frame {}: {error}", path.display());
+                    }
+                }
+                Err(error) => error!("Cannot record frame: {error}"),
+            }
+        });
+        // Only one screenshot can be taken per window and per frame.
+        if requested.is_ok() {
+            recorder.frame += 1;
+        }
+    }
+}
+
+/// Sample the center of each virtual pixel of `area`.
+fn downsample(screenshot: &Image, area: CaptureArea) -> Option<Image> {
+    let format = screenshot.texture_descriptor.format;
+    if format.pixel_size() != 4 {
+        return None;
+    }
+    let width = screenshot.width() as i32;
+    let height = screenshot.height() as i32;
+    let mut data = Vec::with_capacity((area.size.x * area.size.y * 4) as usize);
+    for y in 0..area.size.y {
+        for x in 0..area.size.x {
+            let center = area.origin + (UVec2::new(x, y).as_vec2() + 0.5) * area.pixel_size;
+            let source_x = (center.x as i32).clamp(0, width - 1);
+            let source_y = (center.y as i32).clamp(0, height - 1);
+            let index = ((source_y * width + source_x) * 4) as usize;
+            data.extend_from_slice(screenshot.data.get(index..index + 4)?);
+        }
+    }
+    Some(Image::new(
+        Extent3d {
+            width: area.size.x,
+            height: area.size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::default(),
+    ))
+}
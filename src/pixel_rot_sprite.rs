@@ -0,0 +1,128 @@
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{Material2d, Material2dPlugin, Mesh2dHandle};
+
+const PIXEL_ROT_SPRITE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0xe1a7_3c05_9d24_4f8e_a6b3_52c8_07d9_1f64);
+
+/// Provides the rotation filter for pixel-art sprites (see `PixelRotSprite`).
+pub struct PixelRotSpritePlugin;
+
+impl Plugin for PixelRotSpritePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PIXEL_ROT_SPRITE_SHADER_HANDLE,
+            "pixel_rot_sprite.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(Material2dPlugin::<RotSpriteMaterial>::default())
+            .add_systems(PostUpdate, pixel_rot_sprite_system);
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+/// Draw an image as a sprite that can be rotated at any angle without the
+/// jagged edges of nearest sampling.
+///
+/// The image is sampled through a Scale3x filter (as in the first step of the
+/// RotSprite algorithm), so that diagonals are smoothed before rotation. Use
+/// it instead of a `Sprite` (see `RotSpriteBundle`), and rotate the entity
+/// with its `Transform`.
+///
+/// Requires the `PixelRotSpritePlugin`.
+pub struct PixelRotSprite {
+    /// The image of the sprite.
+    pub image: Handle<Image>,
+    /// The size of the sprite, in world units (by default, the size of the
+    /// image).
+    pub custom_size: Option<Vec2>,
+}
+
+impl PixelRotSprite {
+    /// Draw `image` at its own size.
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            custom_size: None,
+        }
+    }
+}
+
+#[derive(Bundle, Clone, Default)]
+/// A bundle for rotated pixel-art sprites (see `PixelRotSprite`).
+pub struct RotSpriteBundle {
+    pub rot_sprite: PixelRotSprite,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub inherited_visibility: InheritedVisibility,
+    pub view_visibility: ViewVisibility,
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Debug, Clone)]
+/// The material used to draw a `PixelRotSprite`.
+///
+/// It is created and updated by the `PixelRotSpritePlugin`.
+pub struct RotSpriteMaterial {
+    #[texture(0)]
+    texture: Handle<Image>,
+}
+
+impl Material2d for RotSpriteMaterial {
+    fn fragment_shader() -> ShaderRef {
+        PIXEL_ROT_SPRITE_SHADER_HANDLE.into()
+    }
+}
+
+/// The size of the mesh of a `PixelRotSprite`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RotSpriteSize(Vec2);
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_rot_sprite_system(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<RotSpriteMaterial>>,
+    sprites: Query<(
+        Entity,
+        &PixelRotSprite,
+        Option<&Handle<RotSpriteMaterial>>,
+        Option<&RotSpriteSize>,
+    )>,
+) {
+    for (entity, rot_sprite, material, size) in &sprites {
+        match material.and_then(|handle| Some((handle, materials.get(handle)?))) {
+            Some((handle, material)) => {
+                if material.texture != rot_sprite.image {
+                    if let Some(material) = materials.get_mut(handle) {
+                        material.texture = rot_sprite.image.clone();
+                    }
+                }
+            }
+            None => {
+                let material = materials.add(RotSpriteMaterial {
+                    texture: rot_sprite.image.clone(),
+                });
+                commands.entity(entity).insert(material);
+            }
+        }
+
+        // The mesh can only be sized once the image is loaded.
+        let Some(new_size) = rot_sprite.custom_size.or_else(|| {
+            images
+                .get(&rot_sprite.image)
+                .map(|image| image.size().as_vec2())
+        }) else {
+            continue;
+        };
+        if size.map(|size| size.0) != Some(new_size) {
+            let mesh = meshes.add(Rectangle::from_size(new_size));
+            commands
+                .entity(entity)
+                .insert((Mesh2dHandle(mesh), RotSpriteSize(new_size)));
+        }
+    }
+}
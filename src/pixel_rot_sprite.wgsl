@@ -0,0 +1,67 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var sprite_texture: texture_2d<f32>;
+
+fn texel(position: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(textureDimensions(sprite_texture));
+    return textureLoad(sprite_texture, clamp(position, vec2(0), size - 1), 0);
+}
+
+fn same(a: vec4<f32>, b: vec4<f32>) -> bool {
+    return all(abs(a - b) < vec4(0.5 / 255.0));
+}
+
+// Scale3x, evaluated at the sub-texel where the fragment lands: the rotated
+// sprite is sampled from a 3x upscale with smoothed diagonals instead of the
+// original texels, which avoids most of the jagged edges of nearest sampling.
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    let position = mesh.uv * vec2<f32>(textureDimensions(sprite_texture));
+    let p = vec2<i32>(floor(position));
+    let sub = vec2<i32>(min(floor(fract(position) * 3.0), vec2(2.0)));
+
+    let a = texel(p + vec2(-1, -1));
+    let b = texel(p + vec2(0, -1));
+    let c = texel(p + vec2(1, -1));
+    let d = texel(p + vec2(-1, 0));
+    let e = texel(p);
+    let f = texel(p + vec2(1, 0));
+    let g = texel(p + vec2(-1, 1));
+    let h = texel(p + vec2(0, 1));
+    let i = texel(p + vec2(1, 1));
+
+    let db = same(d, b) && !same(b, f) && !same(d, h);
+    let bf = same(b, f) && !same(b, d) && !same(f, h);
+    let dh = same(d, h) && !same(d, b) && !same(h, f);
+    let hf = same(h, f) && !same(d, h) && !same(b, f);
+
+    var color = e;
+    switch sub.y * 3 + sub.x {
+        case 0: {
+            if db { color = d; }
+        }
+        case 1: {
+            if (db && !same(e, c)) || (bf && !same(e, a)) { color = b; }
+        }
+        case 2: {
+            if bf { color = f; }
+        }
+        case 3: {
+            if (db && !same(e, g)) || (dh && !same(e, a)) { color = d; }
+        }
+        case 5: {
+            if (bf && !same(e, i)) || (hf && !same(e, c)) { color = f; }
+        }
+        case 6: {
+            if dh { color = d; }
+        }
+        case 7: {
+            if (dh && !same(e, i)) || (hf && !same(e, g)) { color = h; }
+        }
+        case 8: {
+            if hf { color = f; }
+        }
+        default: {}
+    }
+    return color;
+}
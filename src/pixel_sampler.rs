@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy::render::texture::{ImageFilterMode, ImageSampler};
+use bevy::utils::HashSet;
+
+use crate::PixelZoom;
+
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+/// Check that the images of the sprites use nearest filtering while a
+/// `PixelZoom` camera exists, since linear filtering makes the pixel art
+/// blurry.
+///
+/// This resource is added by `PixelCameraPlugin` (with `sampler_check`): a
+/// warning is logged (once per image) for each sprite image with a linear
+/// sampler, including images using the default sampler when
+/// `ImagePlugin::default_nearest()` is not used. With `fix`, the sampler of
+/// these images is replaced by a nearest sampler instead. Remove the resource
+/// to disable the check.
+pub struct PixelSamplerCheck {
+    /// Replace linear samplers with nearest ones, instead of warning.
+    pub fix: bool,
+    /// Whether the default sampler of `ImagePlugin` is linear.
+    pub(crate) linear_default: bool,
+    checked: HashSet<AssetId<Image>>,
+}
+
+impl PixelSamplerCheck {
+    pub(crate) fn new(linear_default: bool) -> Self {
+        Self {
+            fix: false,
+            linear_default,
+            checked: HashSet::new(),
+        }
+    }
+
+    fn is_linear(&self, sampler: &ImageSampler) -> bool {
+        match sampler {
+            ImageSampler::Default => self.linear_default,
+            ImageSampler::Descriptor(descriptor) => {
+                matches!(descriptor.mag_filter, ImageFilterMode::Linear)
+                    || matches!(descriptor.min_filter, ImageFilterMode::Linear)
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_sampler_check_system(
+    check: Option<ResMut<PixelSamplerCheck>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    cameras: Query<(), With<PixelZoom>>,
+    sprites: Query<&Handle<Image>, (With<Sprite>, Without<PixelZoom>)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(mut check) = check else {
+        image_events.clear();
+        return;
+    };
+    // Images are checked again when they are reloaded or modified.
+    for event in image_events.read() {
+        if let AssetEvent::Modified { id } | AssetEvent::Removed { id } = event {
+            check.checked.remove(id);
+        }
+    }
+    if cameras.is_empty() {
+        return;
+    }
+
+    for handle in &sprites {
+        let id = handle.id();
+        if check.checked.contains(&id) {
+            continue;
+        }
+        let Some(image) = images.get(id) else {
+            // Not loaded yet.
+            continue;
+        };
+        check.checked.insert(id);
+        if !check.is_linear(&image.sampler) {
+            continue;
+        }
+        let path = handle
+            .path()
+            .map_or_else(|| format!("{id:?}"), |path| path.to_string());
+        if check.fix {
+            info!("using a nearest sampler for image {path} (rendered by a pixel camera)");
+            if let Some(image) = images.get_mut(id) {
+                image.sampler = ImageSampler::nearest();
+            }
+        } else {
+            warn!(
+                "image {path} is rendered by a pixel camera with linear filtering, and will be \
+                 blurry (use `ImagePlugin::default_nearest()`, a nearest sampler for this image, \
+                 or set `PixelSamplerCheck::fix`)"
+            );
+        }
+    }
+}
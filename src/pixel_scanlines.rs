@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+use crate::{PixelCanvas, PixelUpscaleMaterial};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Draw dark horizontal lines across each virtual pixel row, when upscaling a
+/// `PixelCanvas`.
+///
+/// The lines are drawn on whole physical pixels, so they stay crisp at every
+/// zoom. This is much cheaper than the full CRT effect, and can be combined
+/// with it.
+pub struct PixelScanlines {
+    /// How much the lines darken the image, between 0.0 and 1.0.
+    pub intensity: f32,
+    /// Height of the lines, as a fraction of a virtual pixel (rounded to whole
+    /// physical pixels, with a minimum of one).
+    pub thickness: f32,
+    /// Position of the lines inside each virtual pixel row, from 0.0 (top) to
+    /// 1.0 (bottom).
+    pub offset: f32,
+}
+
+impl Default for PixelScanlines {
+    fn default() -> Self {
+        Self {
+            intensity: 0.3,
+            thickness: 0.25,
+            offset: 1.0,
+        }
+    }
+}
+
+pub(crate) fn pixel_scanlines_system(
+    mut materials: ResMut<Assets<PixelUpscaleMaterial>>,
+    cameras: Query<(&PixelCanvas, Option<&PixelScanlines>)>,
+) {
+    for (canvas, scanlines) in &cameras {
+        let Some(handle) = canvas.material() else {
+            continue;
+        };
+        let scanlines = scanlines.copied().unwrap_or(PixelScanlines {
+            intensity: 0.0,
+            thickness: 0.0,
+            offset: 0.0,
+        });
+        PixelUpscaleMaterial::update(&mut materials, handle, |material| {
+            material.scanline_intensity = scanlines.intensity.clamp(0.0, 1.0);
+            material.scanline_thickness = scanlines.thickness.clamp(0.0, 1.0);
+            material.scanline_offset = scanlines.offset.clamp(0.0, 1.0);
+        });
+    }
+}
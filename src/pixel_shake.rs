@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::pixel_cursor::virtual_pixel_scale;
+use crate::PixelZoom;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Shake a `PixelZoom` camera, by offsets of whole virtual pixels.
+///
+/// The amplitude of the shake is proportional to the square of the
+/// `trauma` (between 0 and 1), which decreases over time. Add trauma with
+/// `add_trauma` (e.g. on impacts). The offset is applied in `PostUpdate`,
+/// after `PixelCameraFollow` and `PixelCameraBounds`, and removed at the
+/// beginning of the next frame, so the camera is never permanently
+/// displaced.
+pub struct PixelShake {
+    /// The current trauma, between 0 and 1.
+    pub trauma: f32,
+    /// The offset at full trauma, in virtual pixels.
+    pub max_offset: Vec2,
+    /// The trauma removed per second.
+    pub decay: f32,
+    /// The speed of the shake, in oscillations per second.
+    pub frequency: f32,
+    time: f32,
+    offset: Vec2,
+}
+
+impl PixelShake {
+    /// A shake with the specified maximum offset (in virtual pixels), and no
+    /// trauma.
+    pub fn new(max_offset: Vec2) -> Self {
+        Self {
+            trauma: 0.0,
+            max_offset,
+            decay: 1.0,
+            frequency: 15.0,
+            time: 0.0,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Increase the trauma (clamped to 1).
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+impl Default for PixelShake {
+    fn default() -> Self {
+        Self::new(Vec2::splat(8.0))
+    }
+}
+
+/// A smooth pseudo-random signal between -1 and 1.
+fn noise(time: f32, seed: f32) -> f32 {
+    0.5 * (time + seed).sin()
+        + 0.3 * (2.3 * time + 1.7 * seed).sin()
+        + 0.2 * (5.1 * time + 2.9 * seed).sin()
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_shake_system(
+    time: Res<Time>,
+    mut cameras: Query<
+        (
+            &mut PixelShake,
+            &mut Transform,
+            Option<&OrthographicProjection>,
+            Option<&Projection>,
+        ),
+        With<PixelZoom>,
+    >,
+) {
+    let dt = time.delta_seconds();
+    for (mut shake, mut transform, orthographic, projection) in &mut cameras {
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+        let pixel_size = virtual_pixel_scale(orthographic, projection).unwrap_or(1.0);
+        let amplitude = shake.trauma * shake.trauma;
+        shake.time += dt * shake.frequency * std::f32::consts::TAU;
+        let direction = Vec2::new(noise(shake.time, 0.0), noise(shake.time, 17.0));
+        let offset = (direction * shake.max_offset * amplitude).round() * pixel_size;
+        shake.offset = offset;
+        shake.trauma = (shake.trauma - shake.decay * dt).max(0.0);
+        transform.translation += offset.extend(0.0);
+    }
+}
+
+pub(crate) fn restore_shake_offsets(mut cameras: Query<(&mut Transform, &mut PixelShake)>) {
+    for (mut transform, mut shake) in &mut cameras {
+        if shake.offset != Vec2::ZERO {
+            transform.translation -= shake.offset.extend(0.0);
+            shake.offset = Vec2::ZERO;
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::{math, PixelZoom};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// Snap the translation of an entity to the virtual pixel grid (or to a
+/// coarser grid).
+///
+/// The snapping happens in `PostUpdate`, just before the transforms are
+/// propagated, so any movement done in `Update` is taken into account. The
+/// size of a virtual pixel (in world units) is given by the scale of the
+/// orthographic projection of the `PixelZoom` camera.
+///
+/// Add a `SubpixelOffset` to the entity to keep its smooth position.
+pub struct PixelSnap {
+    /// Size of the grid cells, in virtual pixels (e.g. `UVec2::splat(8)` to
+    /// snap to 8x8 tiles).
+    pub grid: UVec2,
+    /// Which transform is snapped.
+    pub mode: SnapMode,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Select the transform modified by `PixelSnap`.
+pub enum SnapMode {
+    /// Snap the `Transform` before propagation. Children of the entity move
+    /// with their parent.
+    #[default]
+    Transform,
+    /// Only snap the `GlobalTransform`, after propagation, and restore it at
+    /// the beginning of the next frame. The rendering is aligned on virtual
+    /// pixels, but the hierarchy (and the children of the entity) still use
+    /// the smooth position.
+    GlobalTransform,
+}
+
+impl PixelSnap {
+    /// Snap to a grid of `width` by `height` virtual pixels.
+    pub fn with_grid(width: u32, height: u32) -> Self {
+        Self {
+            grid: UVec2::new(width, height),
+            mode: SnapMode::default(),
+        }
+    }
+
+    /// Snap the `GlobalTransform` instead of the `Transform`.
+    pub fn global(mut self) -> Self {
+        self.mode = SnapMode::GlobalTransform;
+        self
+    }
+}
+
+impl Default for PixelSnap {
+    fn default() -> Self {
+        Self::with_grid(1, 1)
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+/// The fractional part of the position of a `PixelSnap` entity, removed by
+/// the snapping.
+///
+/// When this component is present, the offset is added back to the
+/// translation at the beginning of the next frame (in `PreUpdate`), so that
+/// game logic always sees the smooth position, while only the rendered
+/// transform is aligned on virtual pixels. Without it, the fractional part of
+/// the position is lost, and slow-moving entities may stall.
+pub struct SubpixelOffset(pub Vec2);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+/// Snap the translation of a `PixelZoom` camera to the virtual pixel grid.
+///
+/// The translation is quantized in `PostUpdate` (after any follow or
+/// smoothing system running in `Update`), and restored at the beginning of
+/// the next frame, so the logical position of the camera stays smooth while
+/// the rendering doesn't shimmer during scrolling.
+pub struct SnapCameraToPixelGrid {
+    pub(crate) offset: Vec2,
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Snap all sprites, as if they had the specified `PixelSnap`.
+///
+/// Entities with their own `PixelSnap` use it instead. Entities marked with
+/// `NoPixelSnap` are excluded, along with all their descendants. Since the
+/// `Transform` of implicitly snapped sprites is not restored (unless they have
+/// a `SubpixelOffset`), `SnapMode::GlobalTransform` is usually preferable.
+pub struct ImplicitPixelSnap(pub Option<PixelSnap>);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Exclude an entity and all its descendants from the `ImplicitPixelSnap`
+/// (e.g. for particles, smooth-scrolling backgrounds or high resolution
+/// overlays).
+///
+/// Descendants with their own `PixelSnap` are still snapped.
+pub struct NoPixelSnap;
+
+/// The type of the query used to look for `NoPixelSnap` ancestors.
+type Excluded<'w, 's> = Query<'w, 's, (), With<NoPixelSnap>>;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_snap_system(
+    cameras: Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+    implicit: Res<ImplicitPixelSnap>,
+    parents: Query<&Parent>,
+    excluded: Excluded,
+    mut entities: Query<
+        (
+            Entity,
+            &mut Transform,
+            Option<&PixelSnap>,
+            Option<&mut SubpixelOffset>,
+        ),
+        (Or<(With<PixelSnap>, With<Sprite>)>, Without<PixelZoom>),
+    >,
+) {
+    let pixel_size = virtual_pixel_size(&cameras);
+    for (entity, mut transform, pixel_snap, offset) in &mut entities {
+        let Some(pixel_snap) = effective_snap(entity, pixel_snap, &implicit, &parents, &excluded)
+        else {
+            continue;
+        };
+        if pixel_snap.mode != SnapMode::Transform {
+            continue;
+        }
+        let cell_size = pixel_size * pixel_snap.grid.max(UVec2::ONE).as_vec2();
+        let snapped = snap(transform.translation, cell_size);
+        if let Some(mut offset) = offset {
+            offset.0 = (transform.translation - snapped).truncate();
+        }
+        if transform.translation != snapped {
+            transform.translation = snapped;
+        }
+    }
+}
+
+/// The snapping that applies to an entity: its own `PixelSnap` if any,
+/// otherwise the `ImplicitPixelSnap`, unless the entity or one of its
+/// ancestors is marked with `NoPixelSnap`.
+fn effective_snap(
+    entity: Entity,
+    pixel_snap: Option<&PixelSnap>,
+    implicit: &ImplicitPixelSnap,
+    parents: &Query<&Parent>,
+    excluded: &Excluded,
+) -> Option<PixelSnap> {
+    if let Some(pixel_snap) = pixel_snap {
+        return Some(*pixel_snap);
+    }
+    let implicit = implicit.0?;
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        if excluded.contains(entity) {
+            return None;
+        }
+        current = parents.get(entity).ok().map(|parent| parent.get());
+    }
+    Some(implicit)
+}
+
+pub(crate) fn restore_subpixel_offsets(mut entities: Query<(&mut Transform, &mut SubpixelOffset)>) {
+    for (mut transform, mut offset) in &mut entities {
+        if offset.0 != Vec2::ZERO {
+            transform.translation += offset.0.extend(0.0);
+            offset.0 = Vec2::ZERO;
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+/// Snap the rotation around the Z axis of an entity to the nearest of `steps`
+/// evenly spaced angles (e.g. 16 or 32 directions).
+///
+/// As with `SubpixelOffset`, the rotation is quantized in `PostUpdate` and the
+/// smooth rotation is restored at the beginning of the next frame, so game
+/// logic is not affected.
+pub struct QuantizedRotation(pub u32);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// The rotation of a `QuantizedRotation` entity before quantization.
+pub(crate) struct SmoothRotation(Option<Quat>);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+/// The offset removed from the `GlobalTransform` of a `PixelSnap` entity in
+/// `SnapMode::GlobalTransform`.
+pub(crate) struct GlobalSnapOffset(Vec2);
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_snap_global_system(
+    mut commands: Commands,
+    cameras: Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+    implicit: Res<ImplicitPixelSnap>,
+    parents: Query<&Parent>,
+    excluded: Excluded,
+    mut entities: Query<
+        (
+            Entity,
+            &mut GlobalTransform,
+            Option<&PixelSnap>,
+            Option<&mut GlobalSnapOffset>,
+        ),
+        (Or<(With<PixelSnap>, With<Sprite>)>, Without<PixelZoom>),
+    >,
+) {
+    let pixel_size = virtual_pixel_size(&cameras);
+    for (entity, mut global_transform, pixel_snap, offset) in &mut entities {
+        let Some(pixel_snap) = effective_snap(entity, pixel_snap, &implicit, &parents, &excluded)
+        else {
+            continue;
+        };
+        if pixel_snap.mode != SnapMode::GlobalTransform {
+            continue;
+        }
+        let cell_size = pixel_size * pixel_snap.grid.max(UVec2::ONE).as_vec2();
+        snap_global_transform(
+            &mut commands,
+            entity,
+            &mut global_transform,
+            offset,
+            cell_size,
+        );
+    }
+}
+
+/// Snap the translation of a `GlobalTransform` to a grid of `cell_size`,
+/// remembering the removed offset so that it can be restored next frame.
+pub(crate) fn snap_global_transform(
+    commands: &mut Commands,
+    entity: Entity,
+    global_transform: &mut GlobalTransform,
+    offset: Option<Mut<GlobalSnapOffset>>,
+    cell_size: Vec2,
+) {
+    let translation = global_transform.translation();
+    let snapped = snap(translation, cell_size);
+    let removed = (translation - snapped).truncate();
+    match offset {
+        Some(mut offset) => offset.0 = removed,
+        None => {
+            commands.entity(entity).insert(GlobalSnapOffset(removed));
+        }
+    }
+    if removed != Vec2::ZERO {
+        *global_transform = translated(global_transform, -removed);
+    }
+}
+
+pub(crate) fn restore_global_snap_offsets(
+    mut entities: Query<(&mut GlobalTransform, &mut GlobalSnapOffset)>,
+) {
+    for (mut global_transform, mut offset) in &mut entities {
+        if offset.0 != Vec2::ZERO {
+            *global_transform = translated(&global_transform, offset.0);
+            offset.0 = Vec2::ZERO;
+        }
+    }
+}
+
+fn translated(global_transform: &GlobalTransform, offset: Vec2) -> GlobalTransform {
+    let mut affine = global_transform.affine();
+    affine.translation += bevy::math::Vec3A::from(offset.extend(0.0));
+    affine.into()
+}
+
+pub(crate) fn quantize_rotation_system(
+    mut commands: Commands,
+    mut entities: Query<(
+        Entity,
+        &mut Transform,
+        &QuantizedRotation,
+        Option<&mut SmoothRotation>,
+    )>,
+) {
+    for (entity, mut transform, quantized, smooth) in &mut entities {
+        if quantized.0 == 0 {
+            continue;
+        }
+        let rotation = transform.rotation;
+        let (z, y, x) = rotation.to_euler(EulerRot::ZYX);
+        let step = std::f32::consts::TAU / (quantized.0 as f32);
+        let z = (z / step).round() * step;
+        let quantized_rotation = Quat::from_euler(EulerRot::ZYX, z, y, x);
+        match smooth {
+            Some(mut smooth) => smooth.0 = Some(rotation),
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(SmoothRotation(Some(rotation)));
+            }
+        }
+        if transform.rotation != quantized_rotation {
+            transform.rotation = quantized_rotation;
+        }
+    }
+}
+
+pub(crate) fn restore_smooth_rotations(mut entities: Query<(&mut Transform, &mut SmoothRotation)>) {
+    for (mut transform, mut smooth) in &mut entities {
+        if let Some(rotation) = smooth.0.take() {
+            if transform.rotation != rotation {
+                transform.rotation = rotation;
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn snap_camera_system(
+    mut cameras: Query<(
+        &mut Transform,
+        &mut SnapCameraToPixelGrid,
+        Option<&OrthographicProjection>,
+        Option<&Projection>,
+    )>,
+) {
+    for (mut transform, mut snapping, orthographic, projection) in &mut cameras {
+        let pixel_size = match (orthographic, projection) {
+            (Some(orthographic), _) => orthographic.scale,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic.scale,
+            _ => continue,
+        };
+        if pixel_size <= 0.0 {
+            continue;
+        }
+        let snapped = snap(transform.translation, Vec2::splat(pixel_size));
+        snapping.offset = (transform.translation - snapped).truncate();
+        if transform.translation != snapped {
+            transform.translation = snapped;
+        }
+    }
+}
+
+pub(crate) fn restore_camera_offsets(
+    mut cameras: Query<(&mut Transform, &mut SnapCameraToPixelGrid)>,
+) {
+    for (mut transform, mut snapping) in &mut cameras {
+        if snapping.offset != Vec2::ZERO {
+            transform.translation += snapping.offset.extend(0.0);
+            snapping.offset = Vec2::ZERO;
+        }
+    }
+}
+
+/// The size of a virtual pixel in world units, i.e. the scale of the
+/// orthographic projection of the first `PixelZoom` camera.
+pub(crate) fn virtual_pixel_size(
+    cameras: &Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+) -> f32 {
+    cameras
+        .iter()
+        .find_map(
+            |(orthographic, projection)| match (orthographic, projection) {
+                (Some(orthographic), _) => Some(orthographic.scale),
+                (None, Some(Projection::Orthographic(orthographic))) => Some(orthographic.scale),
+                _ => None,
+            },
+        )
+        .filter(|scale| *scale > 0.0)
+        .unwrap_or(1.0)
+}
+
+fn snap(translation: Vec3, cell_size: Vec2) -> Vec3 {
+    math::snap_to_grid(translation.truncate(), cell_size).extend(translation.z)
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::text::{Text2dBounds, TextLayoutInfo};
+
+use crate::pixel_snap::{snap_global_transform, virtual_pixel_size, GlobalSnapOffset};
+use crate::PixelZoom;
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Align the text of a `Text2dBundle` on the virtual pixel grid.
+///
+/// The `GlobalTransform` of the text is snapped after propagation (as with
+/// `SnapMode::GlobalTransform`), so the entity keeps its smooth position.
+///
+/// Note that the glyphs are only sharp if the font atlases use nearest
+/// sampling (e.g. with `ImagePlugin::default_nearest()`): a warning is logged
+/// otherwise.
+///
+/// Only available with the `text` feature.
+pub struct PixelSnapText {
+    /// Also round the position of each glyph inside the text layout to whole
+    /// font pixels.
+    pub glyphs: bool,
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Present if the default sampler of the images is linear, until the
+/// corresponding warning has been logged.
+pub(crate) struct LinearTextSampling;
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_snap_text_system(
+    mut commands: Commands,
+    cameras: Query<(Option<&OrthographicProjection>, Option<&Projection>), With<PixelZoom>>,
+    mut texts: Query<
+        (Entity, &mut GlobalTransform, Option<&mut GlobalSnapOffset>),
+        (With<PixelSnapText>, With<Text2dBounds>, Without<PixelZoom>),
+    >,
+) {
+    let pixel_size = virtual_pixel_size(&cameras);
+    for (entity, mut global_transform, offset) in &mut texts {
+        snap_global_transform(
+            &mut commands,
+            entity,
+            &mut global_transform,
+            offset,
+            Vec2::splat(pixel_size),
+        );
+    }
+}
+
+pub(crate) fn snap_text_glyphs(
+    mut texts: Query<(&PixelSnapText, &mut TextLayoutInfo), Changed<TextLayoutInfo>>,
+) {
+    for (snap_text, mut layout) in &mut texts {
+        if !snap_text.glyphs {
+            continue;
+        }
+        for glyph in &mut layout.glyphs {
+            // The position is the center of the glyph: align its corner.
+            let corner = glyph.position - glyph.size / 2.0;
+            glyph.position = corner.round() + glyph.size / 2.0;
+        }
+    }
+}
+
+pub(crate) fn warn_linear_text_sampling(
+    mut commands: Commands,
+    sampling: Option<Res<LinearTextSampling>>,
+    added: Query<(), Added<PixelSnapText>>,
+) {
+    if sampling.is_some() && !added.is_empty() {
+        warn!(
+            "`PixelSnapText` is used, but the default image sampler is linear: the text will be \
+             blurry (use `ImagePlugin::default_nearest()` to fix this)"
+        );
+        commands.remove_resource::<LinearTextSampling>();
+    }
+}
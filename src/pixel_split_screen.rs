@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy::render::camera::{NormalizedRenderTarget, ScalingMode};
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+
+use crate::pixel_zoom::{apply_zoom, camera_target_size, OrthographicQuery, ZoomSettings};
+use crate::{
+    PixelSafeArea, PixelTargetSize, PixelViewport, PixelZoom, PixelZoomChanged, PixelZoomOverride,
+};
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Share the zoom between the `PixelZoom` cameras rendering to the same
+/// target, e.g. for split-screen.
+///
+/// All the cameras with this component and the same render target use the
+/// smallest of their zooms, so that virtual pixels have the same size on the
+/// whole screen, even if the cameras have different target resolutions. The
+/// viewports are updated accordingly. This is not compatible with
+/// `PixelZoomTransition`.
+///
+/// See `PixelCameraCommands::spawn_split_screen`, which adds this component.
+pub struct PixelSplitScreen;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How the window is divided between the two cameras of a split-screen.
+pub enum SplitScreenLayout {
+    /// The first camera on the left half, the second one on the right half.
+    #[default]
+    SideBySide,
+    /// The first camera on the top half, the second one on the bottom half.
+    Stacked,
+}
+
+impl SplitScreenLayout {
+    /// The regions of the two cameras, normalized (see `PixelViewport`).
+    pub fn regions(self) -> [Rect; 2] {
+        match self {
+            SplitScreenLayout::SideBySide => {
+                [Rect::new(0.0, 0.0, 0.5, 1.0), Rect::new(0.5, 0.0, 1.0, 1.0)]
+            }
+            SplitScreenLayout::Stacked => {
+                [Rect::new(0.0, 0.0, 1.0, 0.5), Rect::new(0.0, 0.5, 1.0, 1.0)]
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn split_screen_zoom_system(
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    zoom_override: Res<PixelZoomOverride>,
+    safe_area: Res<PixelSafeArea>,
+    mut zoom_changed_events: EventWriter<PixelZoomChanged>,
+    mut cameras: Query<
+        (
+            Entity,
+            &mut Camera,
+            &PixelZoom,
+            Option<&PixelViewport>,
+            OrthographicQuery,
+            ZoomSettings,
+            Option<&PixelTargetSize>,
+        ),
+        With<PixelSplitScreen>,
+    >,
+    // Kept between frames to reuse the memory.
+    mut zooms: Local<Vec<(Entity, NormalizedRenderTarget, Vec2)>>,
+    mut shared: Local<HashMap<NormalizedRenderTarget, Vec2>>,
+) {
+    let primary_window = primary_window.iter().next();
+    zooms.clear();
+    shared.clear();
+
+    // The zoom each camera would use on its own.
+    for (entity, camera, pixel_zoom, pixel_viewport, _, settings, target_size) in &cameras {
+        let (Some(target), Some((logical_size, physical_size))) = (
+            camera.target.normalize(primary_window),
+            camera_target_size(camera, target_size),
+        ) else {
+            continue;
+        };
+        let zoom = settings.camera_zoom(
+            pixel_zoom,
+            pixel_viewport,
+            &safe_area.for_camera(camera),
+            &zoom_override,
+            logical_size,
+            physical_size,
+            None,
+        );
+        shared
+            .entry(target.clone())
+            .and_modify(|shared| *shared = shared.min(zoom))
+            .or_insert(zoom);
+        zooms.push((entity, target, zoom));
+    }
+
+    // Only the cameras that would otherwise use a larger zoom are modified.
+    for (entity, target, zoom) in zooms.iter() {
+        let shared_zoom = shared[target];
+        if *zoom == shared_zoom {
+            continue;
+        }
+        let Ok((_, mut camera, pixel_zoom, pixel_viewport, mut projection, _, target_size)) =
+            cameras.get_mut(*entity)
+        else {
+            continue;
+        };
+        let Some((logical_size, physical_size)) = camera_target_size(&camera, target_size) else {
+            continue;
+        };
+        let previous_zoom = match projection.scaling_mode() {
+            Some(ScalingMode::WindowSize(previous_zoom)) => Some(previous_zoom),
+            _ => None,
+        };
+        let safe_area = safe_area.for_camera(&camera);
+        let changed = apply_zoom(
+            &mut camera,
+            pixel_zoom,
+            pixel_viewport,
+            &safe_area,
+            &mut projection,
+            shared_zoom,
+            physical_size,
+            logical_size,
+        );
+        if changed {
+            zoom_changed_events.send(PixelZoomChanged::new(
+                *entity,
+                previous_zoom,
+                shared_zoom.y,
+                &camera.viewport,
+            ));
+        }
+    }
+}
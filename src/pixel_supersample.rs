@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+use bevy::render::camera::{NormalizedRenderTarget, RenderTarget};
+use bevy::render::texture::ImageSampler;
+use bevy::render::view::RenderLayers;
+use bevy::window::{PrimaryWindow, WindowRef};
+
+use crate::pixel_default::HelperCamera;
+use crate::render_target::{render_target_image, resize_render_target};
+use crate::{NoPixelSnap, PixelZoom};
+
+#[derive(Component, Debug, Clone)]
+/// Render a `PixelZoom` camera at the next integer zoom above the window size,
+/// and downscale the result with linear filtering to fit the window.
+///
+/// This "sharp bilinear via supersampling" avoids both the bars of integer
+/// scaling and the shimmering of fractional scaling. The camera is redirected
+/// to an intermediate image, which is displayed in the original window by an
+/// additional camera on the render layer `layer`.
+///
+/// Only the `FitSize`, `FitSizeFloat` and `FitSizeHybrid` modes are supported.
+/// When the component is removed (or the camera switches to another mode),
+/// the display camera is despawned, and the camera renders to the window
+/// again.
+pub struct PixelSupersample {
+    /// The render layer used to display the intermediate image.
+    pub layer: u8,
+    state: Option<SupersampleState>,
+}
+
+#[derive(Debug, Clone)]
+struct SupersampleState {
+    window: Entity,
+    image: Handle<Image>,
+    display_camera: Entity,
+    sprite: Entity,
+}
+
+impl PixelSupersample {
+    /// Supersample the camera, displaying the intermediate image on the
+    /// specified render layer.
+    pub fn new(layer: u8) -> Self {
+        Self { layer, state: None }
+    }
+}
+
+impl Default for PixelSupersample {
+    fn default() -> Self {
+        Self::new(RenderLayers::TOTAL_LAYERS as u8 - 1)
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+/// The camera displaying the intermediate image of a `PixelSupersample`.
+pub(crate) struct SupersampleDisplayCamera {
+    camera: Entity,
+    image: Handle<Image>,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn pixel_supersample_system(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    mut cameras: Query<(Entity, &mut Camera, &PixelZoom, &mut PixelSupersample)>,
+    mut display_cameras: Query<&mut Camera, Without<PixelSupersample>>,
+    display_markers: Query<(Entity, &SupersampleDisplayCamera)>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let primary_window = primary_window.iter().next();
+
+    // Despawn the displays of the removed components (or whose camera has been
+    // despawned), and render their camera to the window again.
+    for (entity, display) in &display_markers {
+        let displayed = cameras.get(display.camera).is_ok_and(|(.., supersample)| {
+            supersample
+                .state
+                .as_ref()
+                .is_some_and(|state| state.display_camera == entity)
+        });
+        if displayed {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+        let Ok(window) = display_cameras
+            .get(entity)
+            .map(|camera| camera.target.clone())
+        else {
+            continue;
+        };
+        if let Ok(mut camera) = display_cameras.get_mut(display.camera) {
+            if matches!(&camera.target, RenderTarget::Image(image) if *image == display.image) {
+                camera.target = window;
+            }
+        }
+    }
+
+    for (entity, mut camera, pixel_zoom, mut supersample) in &mut cameras {
+        let target = match pixel_zoom {
+            PixelZoom::FitSize { width, height }
+            | PixelZoom::FitSizeFloat { width, height }
+            | PixelZoom::FitSizeHybrid { width, height, .. } => {
+                UVec2::new((*width).max(1) as u32, (*height).max(1) as u32)
+            }
+            _ => {
+                // Unsupported mode: render to the window again.
+                if let Some(state) = supersample.state.take() {
+                    commands.entity(state.display_camera).despawn_recursive();
+                    if matches!(&camera.target, RenderTarget::Image(image) if *image == state.image)
+                    {
+                        camera.target = RenderTarget::Window(WindowRef::Entity(state.window));
+                    }
+                }
+                continue;
+            }
+        };
+
+        if supersample.state.is_none() {
+            let Some(NormalizedRenderTarget::Window(window_ref)) =
+                camera.target.normalize(primary_window)
+            else {
+                continue;
+            };
+            let image = images.add(render_target_image(target, ImageSampler::linear()));
+            let layer = RenderLayers::layer(supersample.layer);
+            let display_camera = commands
+                .spawn((
+                    Name::new("Supersample Camera"),
+                    HelperCamera,
+                    SupersampleDisplayCamera {
+                        camera: entity,
+                        image: image.clone(),
+                    },
+                    Camera2dBundle {
+                        camera: Camera {
+                            order: camera.order + 1,
+                            target: RenderTarget::Window(WindowRef::Entity(window_ref.entity())),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .id();
+            let sprite = commands
+                .spawn((
+                    Name::new("Supersample Image"),
+                    NoPixelSnap,
+                    SpriteBundle {
+                        texture: image.clone(),
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .set_parent(display_camera)
+                .id();
+            camera.target = RenderTarget::Image(image.clone());
+            supersample.state = Some(SupersampleState {
+                window: window_ref.entity(),
+                image,
+                display_camera,
+                sprite,
+            });
+        }
+
+        let Some(state) = &supersample.state else {
+            continue;
+        };
+        let Ok(window) = windows.get(state.window) else {
+            continue;
+        };
+
+        let physical_size = Vec2::new(
+            window.physical_width() as f32,
+            window.physical_height() as f32,
+        );
+        let zoom = f32::min(
+            physical_size.x / target.x as f32,
+            physical_size.y / target.y as f32,
+        );
+        let render_zoom = f32::max(zoom.ceil(), 1.0) as u32;
+        resize_render_target(&mut images, &state.image, target * render_zoom);
+
+        if let Ok(mut sprite) = sprites.get_mut(state.sprite) {
+            let size = target.as_vec2() * zoom / window.scale_factor();
+            if sprite.custom_size != Some(size) {
+                sprite.custom_size = Some(size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spawn_headless_window, PixelCameraHeadlessPlugin, PixelCameraPlugin};
+
+    fn app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            PixelCameraHeadlessPlugin,
+            PixelCameraPlugin::default(),
+        ));
+        let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+        let camera = app
+            .world
+            .spawn((
+                Camera2dBundle::default(),
+                PixelZoom::FitSize {
+                    width: 320,
+                    height: 180,
+                },
+                PixelSupersample::default(),
+            ))
+            .id();
+        app.update();
+        (app, window, camera)
+    }
+
+    fn displays(app: &mut App) -> usize {
+        app.world
+            .query::<&SupersampleDisplayCamera>()
+            .iter(&app.world)
+            .count()
+    }
+
+    fn sprites(app: &mut App) -> usize {
+        app.world.query::<&Sprite>().iter(&app.world).count()
+    }
+
+    fn renders_to_window(app: &App, camera: Entity, window: Entity) -> bool {
+        matches!(
+            app.world.get::<Camera>(camera).unwrap().target,
+            RenderTarget::Window(WindowRef::Entity(entity)) if entity == window
+        )
+    }
+
+    #[test]
+    fn display_is_despawned_with_the_component() {
+        let (mut app, window, camera) = app();
+        assert_eq!(displays(&mut app), 1);
+        assert_eq!(sprites(&mut app), 1);
+        assert!(!renders_to_window(&app, camera, window));
+
+        app.world.entity_mut(camera).remove::<PixelSupersample>();
+        app.update();
+        assert_eq!(displays(&mut app), 0);
+        assert_eq!(sprites(&mut app), 0);
+        assert!(renders_to_window(&app, camera, window));
+    }
+
+    #[test]
+    fn display_is_despawned_with_the_camera() {
+        let (mut app, _, camera) = app();
+        app.world.despawn(camera);
+        app.update();
+        assert_eq!(displays(&mut app), 0);
+        assert_eq!(sprites(&mut app), 0);
+    }
+
+    #[test]
+    fn display_is_despawned_for_unsupported_modes() {
+        let (mut app, window, camera) = app();
+        app.world.entity_mut(camera).insert(PixelZoom::Fixed(2));
+        app.update();
+        assert_eq!(displays(&mut app), 0);
+        assert_eq!(sprites(&mut app), 0);
+        assert!(renders_to_window(&app, camera, window));
+
+        // Supersampled again with a supported mode.
+        app.world.entity_mut(camera).insert(PixelZoom::FitSize {
+            width: 320,
+            height: 180,
+        });
+        app.update();
+        assert_eq!(displays(&mut app), 1);
+        assert!(!renders_to_window(&app, camera, window));
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+
+use crate::pixel_zoom::{apply_zoom, camera_target_size, OrthographicQuery};
+use crate::{PixelSafeArea, PixelTargetSize, PixelViewport, PixelZoom, PixelZoomChanged};
+
+#[derive(Component, Debug, Clone, PartialEq)]
+/// Animate the changes of zoom of a `PixelZoom` camera.
+///
+/// When the zoom changes (e.g. during a window resize), the scaling mode and
+/// the viewport are interpolated over `duration` seconds before settling on the
+/// new zoom. Note that the camera is not pixel-perfect during the transition.
+///
+/// The `PixelZoomChanged` event is sent once the transition has settled, with
+/// the zoom and viewport it settled on.
+pub struct PixelZoomTransition {
+    /// Duration of the transition, in seconds.
+    pub duration: f32,
+    /// Easing function of the transition.
+    pub easing: PixelZoomEasing,
+    current: Option<Vec2>,
+    animation: Option<Animation>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Easing function used by `PixelZoomTransition`.
+pub enum PixelZoomEasing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Animation {
+    from: Vec2,
+    to: Vec2,
+    elapsed: f32,
+}
+
+impl PixelZoomTransition {
+    /// Create a transition lasting `duration` seconds.
+    pub fn new(duration: f32, easing: PixelZoomEasing) -> Self {
+        Self {
+            duration,
+            easing,
+            current: None,
+            animation: None,
+        }
+    }
+
+    /// Returns true while a transition is in progress.
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    /// Register a new target zoom. Returns the zoom that should be applied
+    /// right away.
+    pub(crate) fn retarget(&mut self, zoom: Vec2) -> Vec2 {
+        let current = match self.current {
+            Some(current) if current != zoom && self.duration > 0.0 => current,
+            _ => {
+                self.current = Some(zoom);
+                self.animation = None;
+                return zoom;
+            }
+        };
+        if self.animation.map(|animation| animation.to) != Some(zoom) {
+            self.animation = Some(Animation {
+                from: current,
+                to: zoom,
+                elapsed: 0.0,
+            });
+        }
+        current
+    }
+
+    fn advance(&mut self, delta: f32) -> Option<Vec2> {
+        let animation = self.animation.as_mut()?;
+        animation.elapsed += delta;
+        let t = (animation.elapsed / self.duration).clamp(0.0, 1.0);
+        let zoom = animation.from.lerp(animation.to, self.easing.ease(t));
+        if t >= 1.0 {
+            self.animation = None;
+        }
+        self.current = Some(zoom);
+        Some(zoom)
+    }
+}
+
+impl Default for PixelZoomTransition {
+    fn default() -> Self {
+        Self::new(0.25, PixelZoomEasing::default())
+    }
+}
+
+impl PixelZoomEasing {
+    fn ease(self, t: f32) -> f32 {
+        match self {
+            PixelZoomEasing::Linear => t,
+            PixelZoomEasing::EaseIn => t * t,
+            PixelZoomEasing::EaseOut => t * (2.0 - t),
+            PixelZoomEasing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn pixel_zoom_transition_system(
+    time: Res<Time>,
+    safe_area: Res<PixelSafeArea>,
+    mut zoom_changed_events: EventWriter<PixelZoomChanged>,
+    mut cameras: Query<(
+        Entity,
+        &mut Camera,
+        &PixelZoom,
+        Option<&PixelViewport>,
+        OrthographicQuery,
+        &mut PixelZoomTransition,
+        Option<&PixelTargetSize>,
+    )>,
+) {
+    for (
+        entity,
+        mut camera,
+        pixel_zoom,
+        pixel_viewport,
+        mut projection,
+        mut transition,
+        target_size,
+    ) in &mut cameras
+    {
+        let Some(from) = transition.animation.map(|animation| animation.from) else {
+            continue;
+        };
+        let Some((logical_size, physical_size)) = camera_target_size(&camera, target_size) else {
+            continue;
+        };
+        if let Some(zoom) = transition.advance(time.delta_seconds()) {
+            let safe_area = safe_area.for_camera(&camera);
+            apply_zoom(
+                &mut camera,
+                pixel_zoom,
+                pixel_viewport,
+                &safe_area,
+                &mut projection,
+                zoom,
+                physical_size,
+                logical_size,
+            );
+            if !transition.is_animating() {
+                zoom_changed_events.send(PixelZoomChanged::new(
+                    entity,
+                    Some(from.y),
+                    zoom.y,
+                    &camera.viewport,
+                ));
+            }
+        }
+    }
+}
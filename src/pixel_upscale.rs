@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayout;
+use bevy::render::render_resource::{
+    AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+};
+use bevy::sprite::{Material2d, Material2dKey};
+
+pub(crate) const PIXEL_UPSCALE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x5d3f_61c2_a0e4_4c8b_9f2e_7b41_8c0d_e6a1);
+pub(crate) const PIXEL_UPSCALE_BINDINGS_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x2b94_e0d7_5a13_4f6c_8e21_d3c9_706b_a45f);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The filter used to upscale a `PixelCanvas` to the window.
+pub enum UpscaleFilter {
+    /// Each texel covers a whole number of physical pixels, or its neighbour's
+    /// size plus one. With fractional zooms, this makes some virtual pixels
+    /// wider than others, which is visible when scrolling.
+    #[default]
+    Nearest,
+    /// Nearest sampling for the integer part of the zoom, and bilinear
+    /// sampling only for the fractional part (i.e. a single row or column of
+    /// blended physical pixels on texel edges). Avoids the uneven pixels of
+    /// `Nearest` with fractional zooms, at the cost of a slight blur.
+    SharpBilinear,
+    /// A user-provided fragment shader (e.g. xBR, hqx, or a custom CRT
+    /// effect), used in place of the built-in one.
+    ///
+    /// The shader can import the bindings of the upscale pass from the
+    /// `bevy_pixel_camera::upscale` module:
+    ///
+    /// ```wgsl
+    /// #import bevy_sprite::mesh2d_vertex_output::VertexOutput
+    /// #import bevy_pixel_camera::upscale::{upscale, canvas_texture, canvas_sampler}
+    ///
+    /// @fragment
+    /// fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    ///     // Size of the canvas in texels, and physical pixels per texel.
+    ///     let virtual_resolution = upscale.texture_size;
+    ///     let zoom = upscale.scale;
+    ///     ...
+    /// }
+    /// ```
+    ///
+    /// The module also provides `sample_canvas(uv)` (nearest sampling of the
+    /// canvas) and `scanline(uv)` (the darkening of `PixelScanlines`). The
+    /// canvas sampler is linear; sample texel centers for nearest filtering.
+    Custom(Handle<Shader>),
+}
+
+impl UpscaleFilter {
+    pub(crate) fn index(&self) -> u32 {
+        match self {
+            UpscaleFilter::Nearest | UpscaleFilter::Custom(_) => 0,
+            UpscaleFilter::SharpBilinear => 1,
+        }
+    }
+
+    fn shader(&self) -> Option<Handle<Shader>> {
+        match self {
+            UpscaleFilter::Custom(shader) => Some(shader.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Asset, AsBindGroup, TypePath, Debug, Clone, PartialEq)]
+#[bind_group_data(PixelUpscaleKey)]
+/// The material used to display a `PixelCanvas`.
+///
+/// It is created and updated by the `PixelCanvasPlugin`.
+pub struct PixelUpscaleMaterial {
+    /// Size of the canvas, in texels.
+    #[uniform(0)]
+    pub(crate) texture_size: Vec2,
+    /// Number of physical pixels per texel.
+    #[uniform(0)]
+    pub(crate) scale: Vec2,
+    #[uniform(0)]
+    pub(crate) filter_mode: u32,
+    /// Barrel distortion of the CRT effect (zero when disabled).
+    #[uniform(0)]
+    pub(crate) crt_curvature: f32,
+    /// Radius of the rounded corners of the CRT effect, as a fraction of the
+    /// canvas height.
+    #[uniform(0)]
+    pub(crate) crt_corner_radius: f32,
+    #[uniform(0)]
+    pub(crate) crt_mask: u32,
+    #[uniform(0)]
+    pub(crate) crt_mask_intensity: f32,
+    #[uniform(0)]
+    pub(crate) crt_bloom: f32,
+    /// Darkening of the scanlines (zero when disabled).
+    #[uniform(0)]
+    pub(crate) scanline_intensity: f32,
+    /// Height of the scanlines, as a fraction of a virtual pixel.
+    #[uniform(0)]
+    pub(crate) scanline_thickness: f32,
+    /// Position of the scanlines inside each virtual pixel row.
+    #[uniform(0)]
+    pub(crate) scanline_offset: f32,
+    #[texture(1)]
+    #[sampler(2)]
+    pub(crate) texture: Handle<Image>,
+    /// The fragment shader of a custom filter.
+    pub(crate) shader: Option<Handle<Shader>>,
+}
+
+/// The pipeline key of a `PixelUpscaleMaterial`.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PixelUpscaleKey {
+    shader: Option<Handle<Shader>>,
+}
+
+impl From<&PixelUpscaleMaterial> for PixelUpscaleKey {
+    fn from(material: &PixelUpscaleMaterial) -> Self {
+        Self {
+            shader: material.shader.clone(),
+        }
+    }
+}
+
+impl PixelUpscaleMaterial {
+    pub(crate) fn new(texture: Handle<Image>) -> Self {
+        Self {
+            texture_size: Vec2::ONE,
+            scale: Vec2::ONE,
+            filter_mode: 0,
+            crt_curvature: 0.0,
+            crt_corner_radius: 0.0,
+            crt_mask: 0,
+            crt_mask_intensity: 0.0,
+            crt_bloom: 0.0,
+            scanline_intensity: 0.0,
+            scanline_thickness: 0.0,
+            scanline_offset: 0.0,
+            texture,
+            shader: None,
+        }
+    }
+
+    /// Set the parameters that depend on the upscale filter.
+    pub(crate) fn set_filter(&mut self, filter: &UpscaleFilter) {
+        self.filter_mode = filter.index();
+        self.shader = filter.shader();
+    }
+
+    /// Apply `f` to the material, and only mark it as modified if any of its
+    /// parameters changed.
+    pub(crate) fn update(
+        materials: &mut Assets<PixelUpscaleMaterial>,
+        handle: &Handle<PixelUpscaleMaterial>,
+        f: impl FnOnce(&mut PixelUpscaleMaterial),
+    ) {
+        let Some(material) = materials.get(handle) else {
+            return;
+        };
+        let mut updated = material.clone();
+        f(&mut updated);
+        if updated != *material {
+            if let Some(material) = materials.get_mut(handle) {
+                *material = updated;
+            }
+        }
+    }
+}
+
+impl Material2d for PixelUpscaleMaterial {
+    fn fragment_shader() -> ShaderRef {
+        PIXEL_UPSCALE_SHADER_HANDLE.into()
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayout,
+        key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        if let Some(fragment) = &mut descriptor.fragment {
+            if let Some(shader) = &key.bind_group_data.shader {
+                fragment.shader = shader.clone();
+            }
+            #[cfg(feature = "crt")]
+            fragment.shader_defs.push("PIXEL_CRT".into());
+        }
+        Ok(())
+    }
+}
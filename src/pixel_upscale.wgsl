@@ -0,0 +1,69 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import bevy_pixel_camera::upscale::{upscale, canvas_texture, canvas_sampler, sample_canvas, scanline}
+
+const MASK_APERTURE_GRILLE: u32 = 1u;
+const MASK_SLOT: u32 = 2u;
+
+#ifdef PIXEL_CRT
+fn crt_distort(uv: vec2<f32>) -> vec2<f32> {
+    let centered = uv * 2.0 - 1.0;
+    let distorted = centered * (1.0 + upscale.crt_curvature * dot(centered, centered));
+    return distorted * 0.5 + 0.5;
+}
+
+// Signed distance to the rounded rectangle of the screen, in units of the
+// screen height.
+fn crt_corner_distance(uv: vec2<f32>) -> f32 {
+    let aspect = upscale.texture_size.x / upscale.texture_size.y;
+    let half_size = vec2(0.5 * aspect, 0.5);
+    let position = (uv - 0.5) * vec2(aspect, 1.0);
+    let radius = upscale.crt_corner_radius;
+    let q = abs(position) - half_size + radius;
+    return length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+fn crt_mask(frag_coord: vec2<f32>) -> vec3<f32> {
+    let pixel = vec2<u32>(frag_coord);
+    var mask = vec3(1.0);
+    if upscale.crt_mask == MASK_APERTURE_GRILLE || upscale.crt_mask == MASK_SLOT {
+        let column = pixel.x % 3u;
+        mask = vec3(f32(column == 0u), f32(column == 1u), f32(column == 2u));
+        if upscale.crt_mask == MASK_SLOT {
+            let stagger = ((pixel.x / 3u) % 2u) * 2u;
+            if (pixel.y + stagger) % 4u == 0u {
+                mask = vec3(0.0);
+            }
+        }
+    }
+    return mix(vec3(1.0), mask, upscale.crt_mask_intensity);
+}
+#endif
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef PIXEL_CRT
+    // No early return outside the screen: texture sampling requires uniform
+    // control flow.
+    let uv = crt_distort(mesh.uv);
+    var color = sample_canvas(uv);
+    if upscale.crt_bloom > 0.0 {
+        let texel = 1.0 / upscale.texture_size;
+        let glow = (
+            textureSample(canvas_texture, canvas_sampler, uv + vec2(texel.x, 0.0))
+            + textureSample(canvas_texture, canvas_sampler, uv - vec2(texel.x, 0.0))
+            + textureSample(canvas_texture, canvas_sampler, uv + vec2(0.0, texel.y))
+            + textureSample(canvas_texture, canvas_sampler, uv - vec2(0.0, texel.y))
+        ).rgb * 0.25;
+        color = vec4(color.rgb + upscale.crt_bloom * glow * glow, color.a);
+    }
+    color = vec4(color.rgb * scanline(uv) * crt_mask(mesh.position.xy), color.a);
+    // Antialiased rounded corners, one physical pixel wide.
+    let edge = 1.0 / (upscale.texture_size.y * upscale.scale.y);
+    let inside = f32(all(uv >= vec2(0.0)) && all(uv <= vec2(1.0)));
+    let corner = inside * (1.0 - smoothstep(-edge, 0.0, crt_corner_distance(uv)));
+    return vec4(color.rgb * corner, color.a);
+#else
+    let color = sample_canvas(mesh.uv);
+    return vec4(color.rgb * scanline(mesh.uv), color.a);
+#endif
+}
@@ -0,0 +1,53 @@
+#define_import_path bevy_pixel_camera::upscale
+
+// Bindings and helpers of the canvas upscale pass, shared by the built-in
+// filters and by custom ones (see `UpscaleFilter::Custom`).
+
+struct PixelUpscale {
+    // Size of the canvas, in texels (the virtual resolution, plus the margin of
+    // smooth scrolling).
+    texture_size: vec2<f32>,
+    // Number of physical pixels per texel (the zoom, times the scale factor).
+    scale: vec2<f32>,
+    filter_mode: u32,
+    crt_curvature: f32,
+    crt_corner_radius: f32,
+    crt_mask: u32,
+    crt_mask_intensity: f32,
+    crt_bloom: f32,
+    scanline_intensity: f32,
+    scanline_thickness: f32,
+    scanline_offset: f32,
+};
+
+const FILTER_NEAREST: u32 = 0u;
+const FILTER_SHARP_BILINEAR: u32 = 1u;
+
+@group(2) @binding(0) var<uniform> upscale: PixelUpscale;
+@group(2) @binding(1) var canvas_texture: texture_2d<f32>;
+@group(2) @binding(2) var canvas_sampler: sampler;
+
+fn sample_canvas(uv: vec2<f32>) -> vec4<f32> {
+    let texel = uv * upscale.texture_size;
+    var offset = vec2(0.5);
+    if upscale.filter_mode == FILTER_SHARP_BILINEAR {
+        // Nearest sampling for the integer part of the scale, and bilinear
+        // sampling for the remaining fraction of a pixel on texel edges.
+        let prescale = max(floor(upscale.scale), vec2(1.0));
+        let region = 0.5 - 0.5 / prescale;
+        let center_distance = fract(texel) - 0.5;
+        offset = (center_distance - clamp(center_distance, -region, region)) * prescale + 0.5;
+    }
+    return textureSample(canvas_texture, canvas_sampler, (floor(texel) + offset) / upscale.texture_size);
+}
+
+// Darkening factor of the scanlines, computed on whole physical pixels so that
+// they stay crisp.
+fn scanline(uv: vec2<f32>) -> f32 {
+    let scale = max(upscale.scale.y, 1.0);
+    let row = floor(fract(uv.y * upscale.texture_size.y) * scale);
+    let thickness = max(round(upscale.scanline_thickness * scale), 1.0);
+    let start = round(upscale.scanline_offset * max(scale - thickness, 0.0));
+    let on_line = f32(row >= start && row < start + thickness);
+    return 1.0 - upscale.scanline_intensity * on_line;
+}
@@ -1,9 +1,15 @@
+use crate::math::{self, auto_zoom};
+use crate::render_target::resize_render_target;
+use crate::{PixelCameraEnabled, PixelCanvas, PixelZoomTransition};
 use bevy::{
+    ecs::query::QueryData,
+    ecs::system::SystemParam,
     prelude::*,
-    render::camera::{NormalizedRenderTarget, ScalingMode, Viewport},
-    utils::HashSet,
-    window::{PrimaryWindow, WindowCreated, WindowResized},
+    render::camera::{NormalizedRenderTarget, RenderTarget, ScalingMode, Viewport},
+    utils::{tracing, HashSet},
+    window::PrimaryWindow,
 };
+use std::ops::Range;
 
 #[derive(Component, Debug, Clone, PartialEq)]
 /// Configure a `Camera2dBundle` to use integer scaling and automatically match
@@ -24,161 +30,1503 @@ pub enum PixelZoom {
     /// Automatically set the camera zoom to fit the specified height inside the
     /// window.
     FitHeight(i32),
+    /// Automatically set the camera zoom to fit the specified length along the
+    /// larger dimension of the window (i.e. the width in landscape orientation,
+    /// and the height in portrait orientation).
+    ///
+    /// This guarantees a maximum amount of visible world along the long axis,
+    /// while the short axis shows less.
+    FitLargerDim(i32),
+    /// Automatically select, among the specified resolutions, the one whose
+    /// aspect ratio best matches the window, and set the camera zoom to fit it
+    /// inside the window.
+    ///
+    /// This reduces the size of the bars across different screen shapes.
+    FitBest(Vec<UVec2>),
+    /// Automatically set the camera zoom so that at least the specified
+    /// resolution is visible, and let the visible area grow to fill the whole
+    /// window instead of adding bars.
+    ///
+    /// The `PixelViewport` component has no effect in this mode.
+    Expand { min_width: i32, min_height: i32 },
+    /// Automatically set the camera zoom to fit the specified resolution inside
+    /// the window, using a fractional zoom so that it always fills the window
+    /// along one axis.
+    ///
+    /// Note that with a fractional zoom, virtual pixels are not all displayed
+    /// with the same number of screen pixels.
+    FitSizeFloat { width: i32, height: i32 },
+    /// Automatically set the camera zoom to fit the specified resolution inside
+    /// the window, using an integer zoom unless the bars added by the integer
+    /// rounding would exceed `tolerance` (a fraction of the window size, e.g.
+    /// `0.15`), in which case a fractional zoom is used like in
+    /// `FitSizeFloat`.
+    FitSizeHybrid {
+        width: i32,
+        height: i32,
+        tolerance: f32,
+    },
+    /// Map the specified resolution to the whole window, without preserving
+    /// the aspect ratio.
+    ///
+    /// This is *not* a pixel-perfect mode: virtual pixels are neither square
+    /// nor aligned on screen pixels. It is meant for platforms or users that
+    /// explicitly prefer filling the window over pixel accuracy.
+    Stretch { width: i32, height: i32 },
 }
 
 #[derive(Component, Debug, Clone, PartialEq)]
 /// Configure a `Camera2dBundle` to automatically set the viewport so that only
 /// pixels inside the desired resolution (as defined by the `PixelZoom`
 /// component) are displayed.
-pub struct PixelViewport;
+///
+/// This component can be added to or removed from a camera at any time: the
+/// viewport is updated in the same frame, without waiting for a window resize.
+/// Removing it resets the viewport to the whole render target.
+pub struct PixelViewport {
+    /// Position of the viewport inside the window.
+    pub alignment: ViewportAlignment,
+    /// Space kept free between the edges of the region and the viewport. The
+    /// zoom is computed to fit the target resolution inside the remaining
+    /// area.
+    pub margins: ViewportMargins,
+    /// The minimum and maximum depth of the viewport (see `Viewport::depth`).
+    pub depth: Range<f32>,
+    /// The part of the window available to the camera, in normalized
+    /// coordinates (from (0, 0) at the top-left corner to (1, 1) at the
+    /// bottom-right corner). The zoom and viewport are computed within this
+    /// region, which allows several pixel cameras to share a window (e.g. for
+    /// split-screen).
+    pub region: Rect,
+    /// If true (the default), the size of the viewport is always a whole
+    /// number of virtual pixels, even with a fractional scale factor. If false,
+    /// the size is truncated to physical pixels, which may show a partial row
+    /// or column of virtual pixels at the edges.
+    pub snap_to_zoom: bool,
+    /// Strips of the target resolution excluded from the viewport, e.g. to
+    /// leave room for a status bar drawn by another camera. The zoom is still
+    /// computed for the whole target resolution, but the viewport is shrunk
+    /// (and shifted) so that the camera only renders the remaining area.
+    pub reserved: ReservedStrips,
+    /// The axes on which the viewport is cropped to the target resolution.
+    pub crop: ViewportCrop,
+}
 
-pub(crate) fn pixel_zoom_system(
-    mut window_resized_events: EventReader<WindowResized>,
-    mut window_created_events: EventReader<WindowCreated>,
-    mut image_asset_events: EventReader<AssetEvent<Image>>,
-    primary_window: Query<Entity, With<PrimaryWindow>>,
-    mut cameras: Query<(
-        &mut Camera,
-        &PixelZoom,
-        Option<&PixelViewport>,
-        &mut OrthographicProjection,
-    )>,
-) {
-    // Most of the change detection code is copied from `bevy_render/src/camera`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Select which dimensions of a `PixelViewport` are cropped to the target
+/// resolution. The other dimension always fills the window, showing more of
+/// the world.
+pub enum ViewportCrop {
+    /// Crop both dimensions.
+    #[default]
+    Both,
+    /// Only crop the width (bars on the left and right).
+    PillarboxOnly,
+    /// Only crop the height (bars on the top and bottom).
+    LetterboxOnly,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Number of virtual pixels reserved on each edge of a `PixelViewport`.
+pub struct ReservedStrips {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl Default for PixelViewport {
+    fn default() -> Self {
+        Self {
+            alignment: ViewportAlignment::default(),
+            margins: ViewportMargins::default(),
+            depth: 0.0..1.0,
+            region: Rect::new(0.0, 0.0, 1.0, 1.0),
+            snap_to_zoom: true,
+            reserved: ReservedStrips::default(),
+            crop: ViewportCrop::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Margins around the viewport of a `PixelViewport` camera.
+pub struct ViewportMargins {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+    /// The unit in which the margins are expressed.
+    pub unit: MarginUnit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Unit used by `ViewportMargins`.
+pub enum MarginUnit {
+    /// Screen (logical) pixels.
+    #[default]
+    LogicalPixels,
+    /// Virtual pixels, i.e. the margins scale with the zoom.
+    VirtualPixels,
+}
+
+impl ViewportMargins {
+    /// The same margin on all sides.
+    pub fn all(margin: f32, unit: MarginUnit) -> Self {
+        Self {
+            left: margin,
+            right: margin,
+            top: margin,
+            bottom: margin,
+            unit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Position of the viewport of a `PixelViewport` camera inside the window,
+/// when it is smaller than the window.
+pub enum ViewportAlignment {
+    #[default]
+    Center,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// Custom position, where `(0.0, 0.0)` pins the viewport to the top-left
+    /// corner of the window, and `(1.0, 1.0)` to the bottom-right corner.
+    Custom(Vec2),
+}
+
+impl ViewportAlignment {
+    /// The position of the viewport, from `(0.0, 0.0)` (top-left) to
+    /// `(1.0, 1.0)` (bottom-right).
+    pub fn as_vec(self) -> Vec2 {
+        match self {
+            ViewportAlignment::Center => Vec2::new(0.5, 0.5),
+            ViewportAlignment::TopLeft => Vec2::new(0.0, 0.0),
+            ViewportAlignment::TopCenter => Vec2::new(0.5, 0.0),
+            ViewportAlignment::TopRight => Vec2::new(1.0, 0.0),
+            ViewportAlignment::CenterLeft => Vec2::new(0.0, 0.5),
+            ViewportAlignment::CenterRight => Vec2::new(1.0, 0.5),
+            ViewportAlignment::BottomLeft => Vec2::new(0.0, 1.0),
+            ViewportAlignment::BottomCenter => Vec2::new(0.5, 1.0),
+            ViewportAlignment::BottomRight => Vec2::new(1.0, 1.0),
+            ViewportAlignment::Custom(position) => position.clamp(Vec2::ZERO, Vec2::ONE),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Configure how the automatic zoom of a `PixelZoom` camera is rounded to an
+/// integer.
+///
+/// Without this component, the zoom is rounded down.
+pub enum PixelZoomRounding {
+    /// Round the zoom down, so that the target resolution is always entirely
+    /// visible (possibly with bars around it).
+    #[default]
+    Floor,
+    /// Round the zoom up, cropping the edges of the target resolution instead
+    /// of adding bars.
+    ///
+    /// Only the dimension that constrains the zoom is guaranteed to fill the
+    /// window: with `FitSize`, the zoom is the smaller of the horizontal and
+    /// vertical ratios, so there can still be bars along the other dimension
+    /// (e.g. a 1000x500 window with a 320x180 target uses a zoom of 3, which
+    /// crops the height to 500 logical pixels but only covers 960 of the
+    /// width).
+    Ceil,
+    /// Round the zoom to the nearest integer, cropping or adding bars
+    /// depending on which one is smaller.
+    Nearest,
+}
+
+impl PixelZoomRounding {
+    pub(crate) fn round(self, zoom: f32) -> f32 {
+        match self {
+            PixelZoomRounding::Floor => zoom.floor(),
+            PixelZoomRounding::Ceil => zoom.ceil(),
+            PixelZoomRounding::Nearest => zoom.round(),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Add an hysteresis band (in logical pixels) to the automatic zoom of a
+/// `PixelZoom` camera.
+///
+/// The zoom only changes once the window size has moved the specified distance
+/// past the threshold of the new zoom level, which avoids flickering when the
+/// window edge is dragged around that threshold. It has no effect on the
+/// `FitSizeFloat` and `FitSizeHybrid` modes.
+pub struct PixelZoomHysteresis(pub f32);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Restrict the automatic zoom of a `PixelZoom` camera to powers of two (1x,
+/// 2x, 4x, 8x...), rounding it down.
+///
+/// This is useful for upscaling or CRT shaders that only look right at those
+/// zoom levels. It has no effect on the `Fixed`, `FitSizeFloat`,
+/// `FitSizeHybrid` and `Stretch` modes.
+pub struct PixelZoomPowerOfTwo;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+/// Restrict the automatic zoom of a `PixelZoom` camera to an explicit list of
+/// allowed zoom levels.
+///
+/// The largest allowed zoom that is not above the computed one is selected (or
+/// the smallest allowed zoom if they are all above). It has no effect on the
+/// `Fixed`, `FitSizeFloat`, `FitSizeHybrid` and `Stretch` modes, and an empty
+/// list is ignored.
+pub struct PixelZoomSteps(pub Vec<i32>);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Allow the automatic zoom of a `PixelZoom` camera to go below 1 when the
+/// target resolution is larger than the window.
+///
+/// Instead of cropping the target resolution, the zoom is then set to the
+/// largest fraction `1 / n` (i.e. 0.5, 0.33, 0.25...) that fits it inside the
+/// window, so that each screen pixel displays `n` virtual pixels.
+pub struct PixelZoomDownscale;
+
+#[derive(Event, Debug, Clone, PartialEq)]
+/// Sent when the plugin changes the zoom or the viewport of a `PixelZoom`
+/// camera.
+pub struct PixelZoomChanged {
+    /// The camera entity.
+    pub camera: Entity,
+    /// The previous (vertical) zoom, if the camera was already using integer
+    /// scaling.
+    pub old: Option<f32>,
+    /// The new (vertical) zoom.
+    pub new: f32,
+    /// The new viewport of the camera, in physical pixels.
+    pub viewport: Option<URect>,
+}
+
+impl PixelZoomChanged {
+    pub(crate) fn new(
+        camera: Entity,
+        old: Option<f32>,
+        new: f32,
+        viewport: &Option<Viewport>,
+    ) -> Self {
+        Self {
+            camera,
+            old,
+            new,
+            viewport: viewport.as_ref().map(|viewport| {
+                URect::from_corners(
+                    viewport.physical_position,
+                    viewport.physical_position + viewport.physical_size,
+                )
+            }),
+        }
+    }
+}
+
+/// A run condition that is true when the zoom or the viewport of a
+/// `PixelZoom` camera has changed, i.e. when a `PixelZoomChanged` event has
+/// been sent since the last run of the system.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_pixel_camera::pixel_zoom_changed;
+/// fn layout_hud() {
+///     // ...
+/// }
+///
+/// App::new().add_systems(Update, layout_hud.run_if(pixel_zoom_changed()));
+/// ```
+pub fn pixel_zoom_changed() -> impl FnMut(EventReader<PixelZoomChanged>) -> bool + Clone {
+    |mut events: EventReader<PixelZoomChanged>| {
+        let changed = !events.is_empty();
+        events.clear();
+        changed
+    }
+}
+
+#[derive(Event, Debug, Clone, PartialEq)]
+/// Sent when the render target of a `PixelZoom` camera is too small for its
+/// target resolution, even with a zoom of 1 (so part of the scene is cut
+/// off), e.g. to switch to a smaller resolution.
+///
+/// The event is sent each time the zoom is computed, i.e. when the camera is
+/// configured and when the window is resized.
+pub struct PixelTargetTooLarge {
+    /// The camera entity.
+    pub camera: Entity,
+    /// The size available for the viewport, in logical pixels (the whole
+    /// window, without a `PixelViewport`).
+    pub window_size: Vec2,
+    /// The target resolution of the camera, in virtual pixels (0 on an axis
+    /// that is not constrained by the `PixelZoom` mode).
+    pub target: IVec2,
+}
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Force the zoom of all `PixelZoom` cameras, regardless of their automatic
+/// mode.
+///
+/// When set to `Some(zoom)`, cameras behave as if their mode was
+/// `PixelZoom::Fixed(zoom)`, except that the viewport (if any) still matches
+/// their target resolution. Setting it back to `None` restores the automatic
+/// zoom. This is useful for settings menus offering "2x / 3x / Auto" options.
+///
+/// Cameras using the `Stretch` mode are not affected.
+pub struct PixelZoomOverride(pub Option<i32>);
+
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+/// The area of the windows that is obscured by the hardware or the system
+/// (e.g. notches and rounded corners on mobile devices), in logical pixels from
+/// each edge.
+///
+/// The viewport of `PixelViewport` cameras rendering to a window stays inside
+/// the remaining safe area, and their zoom is computed to fit it. The values
+/// are not detected automatically: they should be provided by the application
+/// (e.g. from platform APIs).
+pub struct PixelSafeArea {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl PixelSafeArea {
+    /// The safe area insets that apply to a camera.
+    pub(crate) fn for_camera(&self, camera: &Camera) -> PixelSafeArea {
+        match camera.target {
+            RenderTarget::Window(_) => *self,
+            _ => PixelSafeArea::default(),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Select the kind of screen pixels used by the zoom of a `PixelZoom` camera.
+///
+/// Without this component, the zoom is expressed in logical pixels.
+pub enum ZoomBasis {
+    /// The zoom is the number of logical pixels used to display one virtual
+    /// pixel. On HiDPI displays with a fractional scale factor, virtual pixels
+    /// may not be aligned on physical pixels.
+    #[default]
+    LogicalPixels,
+    /// The zoom is the number of physical (device) pixels used to display one
+    /// virtual pixel, so that the integer scaling guarantee applies to actual
+    /// device pixels whatever the scale factor.
+    PhysicalPixels,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// Use non-square virtual pixels on a `PixelZoom` camera.
+///
+/// The value is the aspect ratio of a virtual pixel (its width divided by its
+/// height), e.g. `8.0 / 7.0` for the NES. The zoom of the camera is the
+/// vertical one, and the horizontal zoom is obtained by multiplying it by the
+/// aspect ratio. Note that the horizontal zoom is usually not an integer.
+pub struct PixelAspectRatio(pub f32);
+
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Resize the image targeted by a `PixelZoom` camera to match its target
+/// resolution.
+///
+/// When this component is present on a camera rendering to an `Image`, the
+/// image is resized to the target resolution multiplied by the zoom each time
+/// the zoom changes (e.g. when a `PixelZoomOverride` is set or when the mode is
+/// `PixelZoom::Fixed`). Dimensions that are not constrained by the mode (e.g.
+/// the height with `FitWidth`) are left unchanged.
+pub struct PixelResizeImage;
+
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+/// Keep the world point under the cursor in place when the window is resized.
+///
+/// When this component is present on a camera with a `PixelZoom`, the camera
+/// translation is adjusted after each resize so that the cursor points at the
+/// same world position as before, then snapped to whole virtual pixels.
+pub struct PixelCursorAnchor {
+    previous_center: Option<Vec2>,
+    previous_zoom: Option<Vec2>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+/// The size of the render target of a `PixelZoom` camera, supplied by the
+/// application.
+///
+/// This is needed for targets whose size is not known by Bevy, e.g. a
+/// `RenderTarget::TextureView` provided by an XR runtime or an external
+/// surface. When this component is present, its size is used instead of the
+/// size reported by the camera to compute the zoom and the viewport. It can be
+/// modified at any time (e.g. when the surface is resized).
+pub struct PixelTargetSize {
+    /// The size of the target, in physical pixels.
+    pub physical: UVec2,
+    /// The ratio of physical pixels to logical pixels.
+    pub scale_factor: f32,
+}
+
+impl PixelTargetSize {
+    /// A target of `physical` pixels, with a scale factor of 1.
+    pub fn new(physical: UVec2) -> Self {
+        Self {
+            physical,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Use the specified scale factor.
+    pub fn with_scale_factor(self, scale_factor: f32) -> Self {
+        Self {
+            scale_factor,
+            ..self
+        }
+    }
+
+    /// The size of the target, in logical pixels.
+    pub fn logical(&self) -> Vec2 {
+        self.physical.as_vec2() / self.scale_factor
+    }
+}
 
-    // TODO: maybe this can be replaced with just monitoring
-    // `OrthographicProjection` for changes?
+/// The logical and physical size of the render target of a camera, from its
+/// `PixelTargetSize` if any, or as reported by Bevy.
+pub(crate) fn camera_target_size(
+    camera: &Camera,
+    target_size: Option<&PixelTargetSize>,
+) -> Option<(Vec2, UVec2)> {
+    match target_size {
+        Some(target_size) if target_size.scale_factor > 0.0 => {
+            Some((target_size.logical(), target_size.physical))
+        }
+        _ => Some((
+            camera.logical_target_size()?,
+            camera.physical_target_size()?,
+        )),
+    }
+}
 
-    let primary_window = primary_window.iter().next();
+/// The size of the render target of a camera when its zoom was last computed,
+/// and the vertical zoom computed then.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ZoomTargetSize {
+    logical: Vec2,
+    physical: UVec2,
+    zoom: Option<f32>,
+}
 
-    let mut changed_window_ids = HashSet::new();
-    changed_window_ids.extend(window_created_events.read().map(|event| event.window));
-    changed_window_ids.extend(window_resized_events.read().map(|event| event.window));
+/// The parameters needed to compute the zoom of the `PixelZoom` cameras.
+///
+/// This is shared by `pixel_zoom_system` and the `PixelCameraCommands`, so
+/// that a camera reconfigured by a command is in the same state as if it had
+/// been updated by the system.
+#[derive(SystemParam)]
+pub(crate) struct ZoomUpdate<'w, 's> {
+    commands: Commands<'w, 's>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    windows: Query<'w, 's, &'static Window>,
+    zoom_override: Res<'w, PixelZoomOverride>,
+    safe_area: Res<'w, PixelSafeArea>,
+    zoom_changed_events: EventWriter<'w, PixelZoomChanged>,
+    too_large_events: EventWriter<'w, PixelTargetTooLarge>,
+    cameras: Query<'w, 's, ZoomCamera, Without<PixelCanvas>>,
+    images: ResMut<'w, Assets<Image>>,
+}
 
-    let changed_image_handles: HashSet<&AssetId<Image>> = image_asset_events
-        .read()
-        .filter_map(|event| {
-            if let AssetEvent::Modified { id } = event {
-                Some(id)
-            } else {
-                None
+/// The components of a camera used by `ZoomUpdate`.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub(crate) struct ZoomCamera {
+    entity: Entity,
+    camera: &'static mut Camera,
+    pixel_zoom: Ref<'static, PixelZoom>,
+    pixel_viewport: Option<Ref<'static, PixelViewport>>,
+    projection: OrthographicQuery,
+    cursor_anchor: Option<&'static mut PixelCursorAnchor>,
+    transform: Option<&'static mut Transform>,
+    settings: ZoomSettings,
+    transition: Option<&'static mut PixelZoomTransition>,
+    resize_image: Has<PixelResizeImage>,
+    target_size: Option<&'static mut ZoomTargetSize>,
+    supplied_size: Option<&'static PixelTargetSize>,
+}
+
+impl ZoomUpdate<'_, '_> {
+    /// The `PixelZoom` cameras (except those of a `PixelCanvas`).
+    pub(crate) fn cameras(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.cameras.iter().map(|camera| camera.entity)
+    }
+
+    /// Update the zoom, scaling mode and viewport of a camera, if anything
+    /// they depend on has changed (or always, if `force` is set).
+    /// `viewport_removed` tells if its `PixelViewport` has just been removed.
+    pub(crate) fn update_camera(&mut self, entity: Entity, force: bool, viewport_removed: bool) {
+        let ZoomUpdate {
+            commands,
+            primary_window,
+            windows,
+            zoom_override,
+            safe_area,
+            zoom_changed_events,
+            too_large_events,
+            cameras,
+            images,
+        } = self;
+        let primary_window = primary_window.iter().next();
+        let Ok(ZoomCameraItem {
+            entity,
+            mut camera,
+            pixel_zoom,
+            pixel_viewport,
+            mut projection,
+            cursor_anchor,
+            transform,
+            settings,
+            transition,
+            resize_image,
+            mut target_size,
+            supplied_size,
+        }) = cameras.get_mut(entity)
+        else {
+            return;
+        };
+
+        // The viewport is only managed while the `PixelViewport` is present.
+        let viewport_removed = pixel_viewport.is_none() && viewport_removed;
+        if viewport_removed {
+            camera.viewport = None;
+        }
+
+        // The size of the render target (window, image or texture view) is
+        // updated by Bevy before this system runs, including after a change
+        // of scale factor.
+        let Some((logical_size, physical_size)) = camera_target_size(&camera, supplied_size) else {
+            return;
+        };
+        let (target_resized, last_zoom) = match &mut target_size {
+            Some(target_size) => {
+                let resized =
+                    target_size.logical != logical_size || target_size.physical != physical_size;
+                if resized {
+                    target_size.logical = logical_size;
+                    target_size.physical = physical_size;
+                }
+                (resized, target_size.zoom)
             }
-        })
-        .collect();
+            None => {
+                commands.entity(entity).insert(ZoomTargetSize {
+                    logical: logical_size,
+                    physical: physical_size,
+                    zoom: None,
+                });
+                (true, None)
+            }
+        };
 
-    for (mut camera, pixel_zoom, pixel_viewport, mut projection) in &mut cameras {
         if let Some(normalized_target) = camera.target.normalize(primary_window) {
-            if is_changed(
-                &normalized_target,
-                &changed_window_ids,
-                &changed_image_handles,
-            ) || camera.is_added()
+            if target_resized
+                || force
+                || camera.is_added()
+                || zoom_override.is_changed()
+                || pixel_zoom.is_changed()
+                // Also true when the `PixelViewport` has just been added.
+                || pixel_viewport
+                    .as_ref()
+                    .is_some_and(|viewport| viewport.is_changed())
+                || settings.is_changed()
+                || viewport_removed
+                || (safe_area.is_changed() && pixel_viewport.is_some())
             {
-                let logical_size = match camera.logical_target_size() {
-                    Some(size) => size,
-                    None => continue,
+                let span = info_span!(
+                    "pixel_zoom",
+                    camera = ?entity,
+                    old_zoom = tracing::field::Empty,
+                    new_zoom = tracing::field::Empty,
+                )
+                .entered();
+                let pixel_zoom = &*pixel_zoom;
+                let pixel_viewport = pixel_viewport.as_deref();
+
+                let stretch = matches!(pixel_zoom, PixelZoom::Stretch { .. });
+                let previous_zoom = match projection.scaling_mode() {
+                    Some(ScalingMode::WindowSize(previous_zoom)) => Some(previous_zoom),
+                    // Non-square pixels use a `Fixed` scaling mode, which
+                    // doesn't tell the zoom.
+                    Some(_) if !stretch => last_zoom,
+                    Some(_) => None,
+                    None => return,
                 };
 
-                let physical_size = match camera.physical_target_size() {
-                    Some(size) => size,
-                    None => continue,
+                let safe_area = safe_area.for_camera(&camera);
+                let zoom = settings.camera_zoom(
+                    pixel_zoom,
+                    pixel_viewport,
+                    &safe_area,
+                    zoom_override,
+                    logical_size,
+                    physical_size,
+                    previous_zoom,
+                );
+                if let Some(previous_zoom) = previous_zoom {
+                    span.record("old_zoom", previous_zoom);
+                }
+                span.record("new_zoom", zoom.y);
+                let zoom_target_size = ZoomTargetSize {
+                    logical: logical_size,
+                    physical: physical_size,
+                    zoom: Some(zoom.y),
                 };
+                match &mut target_size {
+                    Some(target_size) if **target_size != zoom_target_size => {
+                        **target_size = zoom_target_size;
+                    }
+                    Some(_) => {}
+                    None => {
+                        commands.entity(entity).insert(zoom_target_size);
+                    }
+                }
+
+                let available_size =
+                    math::viewport_region(pixel_viewport, &safe_area, logical_size, zoom).size();
+                if let Some(target) = target_too_large(pixel_zoom, available_size, zoom) {
+                    debug!(
+                        "the target resolution of camera {:?} ({} x {}) doesn't fit in {} x {} \
+                         logical pixels",
+                        entity, target.x, target.y, available_size.x, available_size.y,
+                    );
+                    too_large_events.send(PixelTargetTooLarge {
+                        camera: entity,
+                        window_size: available_size,
+                        target,
+                    });
+                }
 
-                let zoom = auto_zoom(pixel_zoom, logical_size) as f32;
-                match projection.scaling_mode {
-                    ScalingMode::WindowSize(previous_zoom) => {
-                        if previous_zoom != zoom {
-                            projection.scaling_mode = ScalingMode::WindowSize(zoom)
+                if let (Some(mut anchor), Some(mut transform)) = (cursor_anchor, transform) {
+                    let cursor = match &normalized_target {
+                        NormalizedRenderTarget::Window(window_ref) => windows
+                            .get(window_ref.entity())
+                            .ok()
+                            .and_then(|window| window.cursor_position()),
+                        _ => None,
+                    }
+                    .filter(|_| !stretch);
+                    // The center of the new viewport, in logical pixels.
+                    let center = match pixel_viewport {
+                        Some(pixel_viewport) => {
+                            let viewport = math::viewport(
+                                pixel_zoom,
+                                pixel_viewport,
+                                &safe_area,
+                                zoom,
+                                physical_size,
+                                logical_size,
+                            );
+                            let scale_factor = (physical_size.x as f32) / logical_size.x;
+                            (viewport.physical_position.as_vec2()
+                                + viewport.physical_size.as_vec2() / 2.0)
+                                / scale_factor
                         }
+                        None => logical_size / 2.0,
+                    };
+                    if let (Some(previous_center), Some(previous_zoom), Some(cursor)) =
+                        (anchor.previous_center, anchor.previous_zoom, cursor)
+                    {
+                        anchor_to_cursor(
+                            &mut transform,
+                            cursor,
+                            (previous_center, previous_zoom),
+                            (center, zoom),
+                            projection.scale(),
+                        );
                     }
-                    _ => projection.scaling_mode = ScalingMode::WindowSize(zoom),
+                    anchor.previous_center = Some(center);
+                    anchor.previous_zoom = Some(zoom);
                 }
 
-                if pixel_viewport.is_some() {
-                    set_viewport(&mut camera, pixel_zoom, zoom, physical_size, logical_size);
+                if let (true, NormalizedRenderTarget::Image(image)) =
+                    (resize_image, &normalized_target)
+                {
+                    let (width, height) = math::target_dimensions(pixel_zoom, logical_size, zoom);
+                    let size = UVec2::new(
+                        width.map_or(physical_size.x, |width| {
+                            ((width as f32) * zoom.x).round() as u32
+                        }),
+                        height.map_or(physical_size.y, |height| {
+                            ((height as f32) * zoom.y).round() as u32
+                        }),
+                    );
+                    resize_render_target(images, image, size);
+                }
+
+                // The event of an animated change is sent once it settles.
+                let (zoom, animating) = match transition {
+                    Some(mut transition) => (transition.retarget(zoom), transition.is_animating()),
+                    None => (zoom, false),
+                };
+                let changed = apply_zoom(
+                    &mut camera,
+                    pixel_zoom,
+                    pixel_viewport,
+                    &safe_area,
+                    &mut projection,
+                    zoom,
+                    physical_size,
+                    logical_size,
+                );
+                if (changed || viewport_removed) && !animating {
+                    zoom_changed_events.send(PixelZoomChanged::new(
+                        entity,
+                        previous_zoom,
+                        zoom.y,
+                        &camera.viewport,
+                    ));
                 }
             }
         }
     }
 }
 
-fn is_changed(
-    render_target: &NormalizedRenderTarget,
-    changed_window_ids: &HashSet<Entity>,
-    changed_image_handles: &HashSet<&AssetId<Image>>,
+pub(crate) fn pixel_zoom_system(
+    mut zoom_update: ZoomUpdate,
+    mut removed_viewports: RemovedComponents<PixelViewport>,
+    enabled: Option<Res<PixelCameraEnabled>>,
+    // Kept between frames to reuse the memory.
+    mut removed: Local<HashSet<Entity>>,
+    mut cameras: Local<Vec<Entity>>,
+) {
+    // Recompute everything when the plugin is enabled again.
+    let enabled_again = enabled.is_some_and(|enabled| enabled.is_changed());
+
+    removed.clear();
+    removed.extend(removed_viewports.read());
+    cameras.clear();
+    cameras.extend(zoom_update.cameras());
+
+    for &entity in cameras.iter() {
+        zoom_update.update_camera(entity, enabled_again, removed.contains(&entity));
+    }
+}
+
+/// The target resolution of `mode` (with 0 for unconstrained axes), if it
+/// doesn't fit in `available_size` logical pixels with `zoom`.
+fn target_too_large(mode: &PixelZoom, available_size: Vec2, zoom: Vec2) -> Option<IVec2> {
+    let (width, height) = math::target_dimensions(mode, available_size, zoom);
+    // Half a logical pixel of tolerance, for rounding errors.
+    let too_large = |length: Option<i32>, zoom: f32, available: f32| {
+        length.is_some_and(|length| length as f32 * zoom > available + 0.5)
+    };
+    (too_large(width, zoom.x, available_size.x) || too_large(height, zoom.y, available_size.y))
+        .then(|| IVec2::new(width.unwrap_or(0), height.unwrap_or(0)))
+}
+
+/// Update the scaling mode and, if needed, the viewport of a camera so that
+/// they match `zoom` (horizontal and vertical). Returns true if anything
+/// changed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_zoom(
+    camera: &mut Mut<Camera>,
+    pixel_zoom: &PixelZoom,
+    pixel_viewport: Option<&PixelViewport>,
+    safe_area: &PixelSafeArea,
+    projection: &mut OrthographicQueryItem,
+    zoom: Vec2,
+    physical_size: UVec2,
+    logical_size: Vec2,
 ) -> bool {
-    match render_target {
-        NormalizedRenderTarget::Window(window_ref) => {
-            changed_window_ids.contains(&window_ref.entity())
-        }
-        NormalizedRenderTarget::Image(image_handle) => {
-            changed_image_handles.contains(&image_handle.id())
+    let _span = info_span!("apply_zoom", zoom = ?zoom).entered();
+    let mut changed = false;
+
+    if let Some(pixel_viewport) = pixel_viewport {
+        let viewport = math::viewport(
+            pixel_zoom,
+            pixel_viewport,
+            safe_area,
+            zoom,
+            physical_size,
+            logical_size,
+        );
+        if !camera
+            .viewport
+            .as_ref()
+            .is_some_and(|current| same_viewport(current, &viewport))
+        {
+            camera.viewport = Some(viewport);
+            changed = true;
         }
-        NormalizedRenderTarget::TextureView(_) => true,
     }
+
+    let scaling_mode = match pixel_zoom {
+        PixelZoom::Stretch { width, height } => ScalingMode::Fixed {
+            width: i32::max(*width, 1) as f32,
+            height: i32::max(*height, 1) as f32,
+        },
+        _ if zoom.x != zoom.y => {
+            // Non-square pixels: the visible area has to be computed from the
+            // size of the viewport.
+            let display_size = match (&camera.viewport, pixel_viewport) {
+                (Some(viewport), Some(_)) => {
+                    let scale_factor = (physical_size.x as f32) / logical_size.x;
+                    viewport.physical_size.as_vec2() / scale_factor
+                }
+                _ => logical_size,
+            };
+            ScalingMode::Fixed {
+                width: display_size.x / zoom.x,
+                height: display_size.y / zoom.y,
+            }
+        }
+        _ => ScalingMode::WindowSize(zoom.y),
+    };
+    changed |= projection.set_scaling_mode(scaling_mode);
+
+    changed
 }
 
-fn auto_zoom(mode: &PixelZoom, logical_size: Vec2) -> i32 {
-    match mode {
-        PixelZoom::FitSize { width, height } => {
-            let zoom_x = (logical_size.x as i32) / i32::max(*width, 1);
-            let zoom_y = (logical_size.y as i32) / i32::max(*height, 1);
-            let zoom = i32::min(zoom_x, zoom_y);
-            i32::max(zoom, 1)
+/// The orthographic projection of a camera: either a bare
+/// `OrthographicProjection` component, or the orthographic variant of a
+/// `Projection` component.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub(crate) struct OrthographicQuery {
+    orthographic: Option<&'static mut OrthographicProjection>,
+    projection: Option<&'static mut Projection>,
+}
+
+impl OrthographicQueryItem<'_> {
+    /// The current scaling mode, or `None` if the camera does not use an
+    /// orthographic projection.
+    pub(crate) fn scaling_mode(&self) -> Option<ScalingMode> {
+        if let Some(orthographic) = &self.orthographic {
+            return Some(orthographic.scaling_mode);
+        }
+        match self.projection.as_deref() {
+            Some(Projection::Orthographic(orthographic)) => Some(orthographic.scaling_mode),
+            _ => None,
+        }
+    }
+
+    /// The scale of the projection, i.e. the size of a virtual pixel in world
+    /// units.
+    pub(crate) fn scale(&self) -> f32 {
+        let scale = match (&self.orthographic, self.projection.as_deref()) {
+            (Some(orthographic), _) => orthographic.scale,
+            (None, Some(Projection::Orthographic(orthographic))) => orthographic.scale,
+            _ => 1.0,
+        };
+        if scale > 0.0 {
+            scale
+        } else {
+            1.0
         }
-        PixelZoom::FitWidth(width) => {
-            let zoom = (logical_size.x as i32) / i32::max(*width, 1);
-            i32::max(zoom, 1)
+    }
+
+    /// Set the scaling mode. The projection is only modified when the scaling
+    /// mode actually changes, to avoid triggering change detection every time.
+    /// Returns true if the scaling mode changed.
+    pub(crate) fn set_scaling_mode(&mut self, scaling_mode: ScalingMode) -> bool {
+        let Some(current) = self.scaling_mode() else {
+            return false;
+        };
+        if same_scaling_mode(current, scaling_mode) {
+            return false;
         }
-        PixelZoom::FitHeight(height) => {
-            let zoom = (logical_size.y as i32) / i32::max(*height, 1);
-            i32::max(zoom, 1)
+        if let Some(orthographic) = &mut self.orthographic {
+            orthographic.scaling_mode = scaling_mode;
+        } else if let Some(projection) = &mut self.projection {
+            if let Projection::Orthographic(orthographic) = projection.as_mut() {
+                orthographic.scaling_mode = scaling_mode;
+            }
         }
-        PixelZoom::Fixed(zoom) => *zoom,
+        true
     }
 }
 
-fn set_viewport(
-    camera: &mut Camera,
-    mode: &PixelZoom,
-    zoom: f32,
-    physical_size: UVec2,
-    logical_size: Vec2,
+/// Move the camera so that the world point under `cursor` stays the same,
+/// given the center of the viewport (in logical pixels) and the zoom, before
+/// and after the change. The translation is snapped to virtual pixels of
+/// `pixel_size` world units.
+fn anchor_to_cursor(
+    transform: &mut Transform,
+    cursor: Vec2,
+    (previous_center, previous_zoom): (Vec2, Vec2),
+    (center, zoom): (Vec2, Vec2),
+    pixel_size: f32,
 ) {
-    let (auto_width, auto_height) = match mode {
-        PixelZoom::FitSize { width, height } => (Some(*width), Some(*height)),
-        PixelZoom::FitWidth(width) => (Some(*width), None),
-        PixelZoom::FitHeight(height) => (None, Some(*height)),
-        PixelZoom::Fixed(..) => (None, None),
+    if previous_center == center && previous_zoom == zoom {
+        return;
+    }
+
+    // Cursor coordinates have their origin at the top-left of the window, with
+    // the Y axis pointing down.
+    let flip = Vec2::new(1.0, -1.0);
+    let previous_offset = (cursor - previous_center) * flip / previous_zoom * pixel_size;
+    let offset = (cursor - center) * flip / zoom * pixel_size;
+    let translation = transform.translation.truncate() + previous_offset - offset;
+    transform.translation =
+        math::snap_to_grid(translation, Vec2::splat(pixel_size)).extend(transform.translation.z);
+}
+
+/// The optional components that affect how the zoom of a camera is computed.
+#[derive(QueryData)]
+pub(crate) struct ZoomSettings {
+    rounding: Option<Ref<'static, PixelZoomRounding>>,
+    hysteresis: Option<Ref<'static, PixelZoomHysteresis>>,
+    power_of_two: Option<Ref<'static, PixelZoomPowerOfTwo>>,
+    steps: Option<Ref<'static, PixelZoomSteps>>,
+    aspect_ratio: Option<Ref<'static, PixelAspectRatio>>,
+    downscale: Option<Ref<'static, PixelZoomDownscale>>,
+    basis: Option<Ref<'static, ZoomBasis>>,
+}
+
+impl ZoomSettingsItem<'_> {
+    /// Whether any of the settings has been added or modified since the last
+    /// run of the system.
+    pub(crate) fn is_changed(&self) -> bool {
+        fn changed<T>(component: &Option<Ref<T>>) -> bool {
+            component
+                .as_ref()
+                .is_some_and(|component| component.is_changed())
+        }
+        changed(&self.rounding)
+            || changed(&self.hysteresis)
+            || changed(&self.power_of_two)
+            || changed(&self.steps)
+            || changed(&self.aspect_ratio)
+            || changed(&self.downscale)
+            || changed(&self.basis)
+    }
+
+    /// Compute the horizontal and vertical zoom of a camera, in logical
+    /// pixels, taking into account the zoom override, the safe area and the
+    /// margins of the viewport. If `previous_zoom` is given, the hysteresis
+    /// band (if any) is taken into account.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn camera_zoom(
+        &self,
+        pixel_zoom: &PixelZoom,
+        pixel_viewport: Option<&PixelViewport>,
+        safe_area: &PixelSafeArea,
+        zoom_override: &PixelZoomOverride,
+        logical_size: Vec2,
+        physical_size: UVec2,
+        previous_zoom: Option<f32>,
+    ) -> Vec2 {
+        let overridden;
+        let pixel_zoom = match zoom_override.0 {
+            Some(zoom) if !matches!(pixel_zoom, PixelZoom::Stretch { .. }) => {
+                overridden = PixelZoom::Fixed(zoom);
+                &overridden
+            }
+            _ => pixel_zoom,
+        };
+        let scale_factor = (physical_size.x as f32) / logical_size.x;
+        let available_size = |zoom: Vec2| {
+            math::viewport_region(pixel_viewport, safe_area, logical_size, zoom).size()
+        };
+
+        let mut zoom = self.zoom(
+            pixel_zoom,
+            available_size(Vec2::ZERO),
+            scale_factor,
+            previous_zoom,
+        );
+        if pixel_viewport.is_some_and(|viewport| viewport.margins.unit == MarginUnit::VirtualPixels)
+        {
+            // Margins in virtual pixels shrink the available area when the zoom
+            // grows, so look for a zoom that fits with its own margins.
+            for _ in 0..8 {
+                let fitted = self.zoom(
+                    pixel_zoom,
+                    available_size(zoom),
+                    scale_factor,
+                    previous_zoom,
+                );
+                if fitted == zoom {
+                    break;
+                }
+                zoom = fitted;
+            }
+        }
+        zoom
+    }
+
+    fn zoom(
+        &self,
+        pixel_zoom: &PixelZoom,
+        logical_size: Vec2,
+        scale_factor: f32,
+        previous_zoom: Option<f32>,
+    ) -> Vec2 {
+        let scale_factor = match self.basis.as_deref() {
+            Some(ZoomBasis::PhysicalPixels) => scale_factor,
+            _ => 1.0,
+        };
+        let aspect_ratio = match self.aspect_ratio.as_deref() {
+            Some(PixelAspectRatio(ratio)) if *ratio > 0.0 => *ratio,
+            _ => 1.0,
+        };
+
+        // Fitting wide virtual pixels in the window is the same as fitting
+        // square pixels in a narrower window.
+        let size = logical_size * scale_factor / Vec2::new(aspect_ratio, 1.0);
+        let previous_zoom = previous_zoom.map(|zoom| zoom * scale_factor);
+        let zoom = self.hysteresis_zoom(pixel_zoom, size, previous_zoom, scale_factor);
+        let zoom = zoom / scale_factor;
+        Vec2::new(zoom * aspect_ratio, zoom)
+    }
+
+    fn hysteresis_zoom(
+        &self,
+        pixel_zoom: &PixelZoom,
+        size: Vec2,
+        previous_zoom: Option<f32>,
+        scale_factor: f32,
+    ) -> f32 {
+        let zoom = self.constrained_zoom(pixel_zoom, size);
+        let (Some(hysteresis), Some(previous_zoom)) = (self.hysteresis.as_deref(), previous_zoom)
+        else {
+            return zoom;
+        };
+        if let PixelZoom::FitSizeFloat { .. } | PixelZoom::FitSizeHybrid { .. } = pixel_zoom {
+            // There are no thresholds in fractional mode.
+            return zoom;
+        }
+
+        let band = Vec2::splat(hysteresis.0 * scale_factor);
+        if zoom > previous_zoom {
+            let lower = self.constrained_zoom(pixel_zoom, size - band);
+            f32::max(lower, previous_zoom)
+        } else if zoom < previous_zoom {
+            let upper = self.constrained_zoom(pixel_zoom, size + band);
+            f32::min(upper, previous_zoom)
+        } else {
+            zoom
+        }
+    }
+
+    fn constrained_zoom(&self, pixel_zoom: &PixelZoom, size: Vec2) -> f32 {
+        let rounding = self.rounding.as_deref().copied().unwrap_or_default();
+        let downscale = self.downscale.is_some();
+        let mut zoom = auto_zoom(pixel_zoom, rounding, downscale, size);
+        if let PixelZoom::Fixed(..)
+        | PixelZoom::FitSizeFloat { .. }
+        | PixelZoom::FitSizeHybrid { .. }
+        | PixelZoom::Stretch { .. } = pixel_zoom
+        {
+            return zoom;
+        }
+        if zoom < 1.0 {
+            // Downscaled, the constraints only apply to magnification.
+            return zoom;
+        }
+
+        if self.power_of_two.is_some() {
+            let exponent = f32::max(zoom, 1.0).log2().floor();
+            zoom = exponent.exp2();
+        }
+        if let Some(PixelZoomSteps(steps)) = self.steps.as_deref() {
+            let steps = steps.iter().filter(|&&step| step > 0);
+            let below = steps.clone().filter(|&&step| step as f32 <= zoom).max();
+            if let Some(&step) = below.or_else(|| steps.min()) {
+                zoom = step as f32;
+            }
+        }
+        zoom
+    }
+}
+
+pub(crate) fn same_viewport(a: &Viewport, b: &Viewport) -> bool {
+    a.physical_position == b.physical_position
+        && a.physical_size == b.physical_size
+        && a.depth == b.depth
+}
+
+fn same_scaling_mode(a: ScalingMode, b: ScalingMode) -> bool {
+    match (a, b) {
+        (ScalingMode::WindowSize(a), ScalingMode::WindowSize(b)) => a == b,
+        (
+            ScalingMode::Fixed {
+                width: a_width,
+                height: a_height,
+            },
+            ScalingMode::Fixed {
+                width: b_width,
+                height: b_height,
+            },
+        ) => a_width == b_width && a_height == b_height,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        resize_headless_window, set_headless_scale_factor, spawn_headless_window,
+        PixelCameraHeadlessPlugin, PixelCameraPlugin, PixelZoomEasing,
     };
 
-    let scale_factor = (physical_size.x as f32) / logical_size.x;
+    fn app(pixel_zoom: PixelZoom, viewport: Option<PixelViewport>) -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            PixelCameraHeadlessPlugin,
+            PixelCameraPlugin::default(),
+        ));
+        let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+        let mut camera = app.world.spawn((Camera2dBundle::default(), pixel_zoom));
+        if let Some(viewport) = viewport {
+            camera.insert(viewport);
+        }
+        let camera = camera.id();
+        app.update();
+        (app, window, camera)
+    }
 
-    let mut viewport_width = physical_size.x;
-    let mut viewport_x = 0;
-    if let Some(target_width) = auto_width {
-        let logical_target_width = (target_width as f32) * zoom;
-        viewport_width = (scale_factor * logical_target_width) as u32;
-        viewport_x = (scale_factor * (logical_size.x - logical_target_width)) as u32 / 2;
+    fn zoom(app: &App, camera: Entity) -> Option<f32> {
+        match app
+            .world
+            .get::<OrthographicProjection>(camera)?
+            .scaling_mode
+        {
+            ScalingMode::WindowSize(zoom) => Some(zoom),
+            _ => None,
+        }
     }
 
-    let mut viewport_height = physical_size.y;
-    let mut viewport_y = 0;
-    if let Some(target_height) = auto_height {
-        let logicat_target_height = (target_height as f32) * zoom;
-        viewport_height = (scale_factor * logicat_target_height) as u32;
-        viewport_y = (scale_factor * (logical_size.y - logicat_target_height)) as u32 / 2;
+    /// The vertical zoom, also with the `Fixed` scaling mode of non-square
+    /// pixels (for cameras without viewport).
+    fn vertical_zoom(app: &App, camera: Entity) -> Option<f32> {
+        let projection = app.world.get::<OrthographicProjection>(camera)?;
+        match projection.scaling_mode {
+            ScalingMode::WindowSize(zoom) => Some(zoom),
+            ScalingMode::Fixed { height, .. } => {
+                let camera = app.world.get::<Camera>(camera)?;
+                Some(camera.logical_target_size()?.y / height)
+            }
+            _ => None,
+        }
     }
 
-    camera.viewport = Some(Viewport {
-        physical_position: UVec2 {
-            x: viewport_x,
-            y: viewport_y,
-        },
-        physical_size: UVec2 {
-            x: viewport_width,
-            y: viewport_height,
-        },
-        ..Default::default()
-    });
+    fn viewport(app: &App, camera: Entity) -> Viewport {
+        app.world
+            .get::<Camera>(camera)
+            .unwrap()
+            .viewport
+            .clone()
+            .unwrap()
+    }
+
+    fn resize(app: &mut App, window: Entity, width: f32, height: f32) {
+        resize_headless_window(&mut app.world, window, width, height);
+        app.update();
+    }
+
+    fn drain_zoom_changed(app: &mut App) -> usize {
+        app.world
+            .resource_mut::<Events<PixelZoomChanged>>()
+            .drain()
+            .count()
+    }
+
+    #[test]
+    fn zoom_is_computed_for_a_new_camera() {
+        let (app, _, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        assert_eq!(zoom(&app, camera), Some(2.0));
+    }
+
+    #[test]
+    fn zoom_follows_window_resizes() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        resize(&mut app, window, 1280.0, 720.0);
+        assert_eq!(zoom(&app, camera), Some(4.0));
+    }
+
+    #[test]
+    fn viewport_follows_scale_factor_changes() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            Some(PixelViewport::default()),
+        );
+        set_headless_scale_factor(&mut app.world, window, 2.0);
+        app.update();
+        // Same logical size, so same zoom, but twice as many physical pixels.
+        assert_eq!(zoom(&app, camera), Some(2.0));
+        let viewport = viewport(&app, camera);
+        assert_eq!(viewport.physical_position, UVec2::new(160, 240));
+        assert_eq!(viewport.physical_size, UVec2::new(1280, 720));
+    }
+
+    #[test]
+    fn zoom_follows_pixel_zoom_mutations() {
+        let (mut app, _, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        *app.world.get_mut::<PixelZoom>(camera).unwrap() = PixelZoom::Fixed(3);
+        app.update();
+        assert_eq!(zoom(&app, camera), Some(3.0));
+    }
+
+    #[test]
+    fn viewport_is_centered() {
+        let (app, _, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            Some(PixelViewport::default()),
+        );
+        let viewport = viewport(&app, camera);
+        assert_eq!(viewport.physical_position, UVec2::new(80, 120));
+        assert_eq!(viewport.physical_size, UVec2::new(640, 360));
+    }
+
+    #[test]
+    fn idle_frames_change_nothing() {
+        let (mut app, window, _) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            Some(PixelViewport::default()),
+        );
+        app.update();
+        drain_zoom_changed(&mut app);
+        app.update();
+        app.update();
+        assert_eq!(drain_zoom_changed(&mut app), 0);
+
+        resize(&mut app, window, 1280.0, 720.0);
+        assert_eq!(drain_zoom_changed(&mut app), 1);
+    }
+
+    #[test]
+    fn transition_reports_the_zoom_it_settles_on() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            Some(PixelViewport::default()),
+        );
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        app.world
+            .entity_mut(camera)
+            .insert(PixelZoomTransition::new(0.25, PixelZoomEasing::Linear));
+        drain_zoom_changed(&mut app);
+        // The first zoom is applied right away.
+        resize(&mut app, window, 960.0, 540.0);
+        assert_eq!(drain_zoom_changed(&mut app), 1);
+
+        resize(&mut app, window, 1280.0, 720.0);
+        let animating = |app: &App| {
+            app.world
+                .get::<PixelZoomTransition>(camera)
+                .unwrap()
+                .is_animating()
+        };
+        assert!(animating(&app));
+        assert_eq!(drain_zoom_changed(&mut app), 0);
+        while animating(&app) {
+            app.update();
+        }
+        let events: Vec<_> = app
+            .world
+            .resource_mut::<Events<PixelZoomChanged>>()
+            .drain()
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].old, Some(3.0));
+        assert_eq!(events[0].new, 4.0);
+        assert_eq!(events[0].viewport, Some(URect::new(0, 0, 1280, 720)));
+        assert_eq!(zoom(&app, camera), Some(4.0));
+    }
+
+    #[test]
+    fn cursor_anchor_keeps_the_world_point_under_the_cursor() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            Some(PixelViewport {
+                alignment: ViewportAlignment::TopLeft,
+                ..Default::default()
+            }),
+        );
+        app.world
+            .entity_mut(camera)
+            .insert(PixelCursorAnchor::default());
+        resize(&mut app, window, 700.0, 500.0);
+        let cursor = Vec2::new(101.0, 51.0);
+        app.world
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(Some(cursor));
+        app.update();
+
+        let world_point = |app: &App| {
+            // Without the transform propagation in `MinimalPlugins`.
+            let camera_transform =
+                GlobalTransform::from(*app.world.get::<Transform>(camera).unwrap());
+            let camera = app.world.get::<Camera>(camera).unwrap();
+            let viewport = camera.logical_viewport_rect().unwrap();
+            camera
+                .viewport_to_world_2d(&camera_transform, cursor - viewport.min)
+                .unwrap()
+        };
+        let before = world_point(&app);
+        // The viewport is pinned to the top-left corner, not centered.
+        resize(&mut app, window, 1280.0, 720.0);
+        app.update();
+        assert_eq!(zoom(&app, camera), Some(4.0));
+        let after = world_point(&app);
+        assert!(
+            before.abs_diff_eq(after, 0.5),
+            "moved from {before} to {after}"
+        );
+    }
+
+    #[test]
+    fn disabled_plugin_leaves_cameras_untouched() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        app.insert_resource(PixelCameraEnabled(false));
+        app.world
+            .get_mut::<OrthographicProjection>(camera)
+            .unwrap()
+            .scaling_mode = ScalingMode::WindowSize(1.0);
+        resize(&mut app, window, 1280.0, 720.0);
+        assert_eq!(zoom(&app, camera), Some(1.0));
+
+        app.insert_resource(PixelCameraEnabled(true));
+        app.update();
+        assert_eq!(zoom(&app, camera), Some(4.0));
+    }
+
+    #[test]
+    fn hysteresis_delays_zoom_changes() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        app.world
+            .entity_mut(camera)
+            .insert(PixelZoomHysteresis(20.0));
+        app.update();
+        assert_eq!(zoom(&app, camera), Some(2.0));
+
+        // Past the threshold of zoom 3 (960), but inside the band.
+        resize(&mut app, window, 970.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(2.0));
+        resize(&mut app, window, 990.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(3.0));
+        // Back under the threshold, but inside the band.
+        resize(&mut app, window, 950.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(3.0));
+        resize(&mut app, window, 930.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(2.0));
+    }
+
+    #[test]
+    fn hysteresis_works_with_non_square_pixels() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        app.world
+            .entity_mut(camera)
+            .insert((PixelZoomHysteresis(20.0), PixelAspectRatio(1.2)));
+        app.update();
+        assert_eq!(vertical_zoom(&app, camera), Some(2.0));
+
+        // The threshold of zoom 3 is at 3 * 320 * 1.2 = 1152 logical pixels,
+        // and the band is measured in the narrowed size (20 * 1.2 = 24).
+        resize(&mut app, window, 1160.0, 600.0);
+        assert_eq!(vertical_zoom(&app, camera), Some(2.0));
+        resize(&mut app, window, 1200.0, 600.0);
+        assert_eq!(vertical_zoom(&app, camera), Some(3.0));
+        resize(&mut app, window, 1140.0, 600.0);
+        assert_eq!(vertical_zoom(&app, camera), Some(3.0));
+    }
+
+    #[test]
+    fn power_of_two_rounds_the_zoom_down() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        app.world.entity_mut(camera).insert(PixelZoomPowerOfTwo);
+        resize(&mut app, window, 1000.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(2.0));
+        resize(&mut app, window, 1300.0, 760.0);
+        assert_eq!(zoom(&app, camera), Some(4.0));
+        resize(&mut app, window, 2500.0, 1400.0);
+        assert_eq!(zoom(&app, camera), Some(4.0));
+    }
+
+    #[test]
+    fn steps_select_an_allowed_zoom() {
+        let (mut app, window, camera) = app(
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            None,
+        );
+        app.world
+            .entity_mut(camera)
+            .insert(PixelZoomSteps(vec![2, 4, 6]));
+        resize(&mut app, window, 1000.0, 600.0);
+        assert_eq!(zoom(&app, camera), Some(2.0));
+        resize(&mut app, window, 1700.0, 950.0);
+        assert_eq!(zoom(&app, camera), Some(4.0));
+        // Below all the steps: the smallest one is used.
+        resize(&mut app, window, 400.0, 300.0);
+        assert_eq!(zoom(&app, camera), Some(2.0));
+    }
 }
@@ -0,0 +1,303 @@
+use std::marker::PhantomData;
+
+use bevy::core_pipeline::core_2d::graph::{Core2d, Node2d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::component::TableStorage;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::extract_component::{
+    ComponentUniforms, DynamicUniformIndex, UniformComponentPlugin,
+};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::encase::internal::WriteInto;
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::{ExtractedView, ViewTarget};
+use bevy::render::{Render, RenderApp, RenderSet};
+
+/// A fullscreen post-processing pass of the 2D render graph, configured by a
+/// uniform extracted from the cameras.
+///
+/// The shader of the pass is bound to the frame (texture and sampler), then
+/// to the `PostProcessTexture` of the view if `TEXTURE` is set, then to the
+/// uniform.
+pub(crate) trait PostProcess: Component + ShaderType + WriteInto + Clone {
+    /// The label of the render graph node.
+    type Label: RenderLabel + Default;
+    /// The prefix of the names of the render resources.
+    const NAME: &'static str;
+    /// The fragment shader.
+    const SHADER: Handle<Shader>;
+    /// Whether the pass uses an additional texture.
+    const TEXTURE: bool = false;
+}
+
+/// The additional texture of a post-processing pass, extracted from the
+/// cameras along with the uniform `U`.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct PostProcessTexture<U>(pub(crate) Handle<Image>, PhantomData<fn() -> U>);
+
+impl<U> PostProcessTexture<U> {
+    pub(crate) fn new(image: Handle<Image>) -> Self {
+        Self(image, PhantomData)
+    }
+}
+
+impl<U: 'static> Component for PostProcessTexture<U> {
+    type Storage = TableStorage;
+}
+
+/// Adds the render graph node of the post-processing pass `U`, between the
+/// contrast adaptive sharpening and the end of the main post-processing
+/// passes. The uniform must be extracted separately (e.g. with an
+/// `ExtractComponentPlugin`).
+pub(crate) struct PostProcessPlugin<U>(PhantomData<fn() -> U>);
+
+impl<U> Default for PostProcessPlugin<U> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<U: PostProcess> Plugin for PostProcessPlugin<U> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(UniformComponentPlugin::<U>::default());
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<SpecializedRenderPipelines<PostProcessPipeline<U>>>()
+            .add_systems(Render, prepare_pipelines::<U>.in_set(RenderSet::Prepare))
+            .add_render_graph_node::<ViewNodeRunner<PostProcessNode<U>>>(
+                Core2d,
+                U::Label::default(),
+            )
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::ConstrastAdaptiveSharpening,
+                    U::Label::default(),
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<PostProcessPipeline<U>>();
+    }
+}
+
+/// The size of a world unit (i.e. a virtual pixel, for `PixelZoom` cameras) in
+/// physical pixels.
+pub(crate) fn physical_pixels_per_unit(
+    camera: &Camera,
+    orthographic: Option<&OrthographicProjection>,
+    projection: Option<&Projection>,
+) -> Vec2 {
+    let area = match (orthographic, projection) {
+        (Some(orthographic), _) => orthographic.area,
+        (None, Some(Projection::Orthographic(orthographic))) => orthographic.area,
+        _ => return Vec2::ONE,
+    };
+    let Some(viewport_size) = camera.physical_viewport_size() else {
+        return Vec2::ONE;
+    };
+    if area.width() <= 0.0 || area.height() <= 0.0 {
+        return Vec2::ONE;
+    }
+    (viewport_size.as_vec2() / area.size()).max(Vec2::ONE)
+}
+
+#[derive(Resource)]
+struct PostProcessPipeline<U> {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    marker: PhantomData<fn() -> U>,
+}
+
+impl<U: PostProcess> FromWorld for PostProcessPipeline<U> {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+        let label = format!("{}_bind_group_layout", U::NAME);
+        let frame = texture_2d(TextureSampleType::Float { filterable: true });
+        let frame_sampler = sampler(SamplerBindingType::Filtering);
+        let layout = if U::TEXTURE {
+            render_device.create_bind_group_layout(
+                label.as_str(),
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::FRAGMENT,
+                    (
+                        frame,
+                        frame_sampler,
+                        texture_2d(TextureSampleType::Float { filterable: true }),
+                        uniform_buffer::<U>(true),
+                    ),
+                ),
+            )
+        } else {
+            render_device.create_bind_group_layout(
+                label.as_str(),
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::FRAGMENT,
+                    (frame, frame_sampler, uniform_buffer::<U>(true)),
+                ),
+            )
+        };
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        Self {
+            layout,
+            sampler,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<U: PostProcess> SpecializedRenderPipeline for PostProcessPipeline<U> {
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some(format!("{}_pipeline", U::NAME).into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: U::SHADER,
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+struct ViewPostProcessPipeline<U>(CachedRenderPipelineId, PhantomData<fn() -> U>);
+
+impl<U: 'static> Component for ViewPostProcessPipeline<U> {
+    type Storage = TableStorage;
+}
+
+fn prepare_pipelines<U: PostProcess>(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessPipeline<U>>>,
+    post_process_pipeline: Res<PostProcessPipeline<U>>,
+    views: Query<(Entity, &ExtractedView), With<U>>,
+) {
+    for (entity, view) in &views {
+        let format = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &post_process_pipeline, format);
+        commands
+            .entity(entity)
+            .insert(ViewPostProcessPipeline::<U>(pipeline_id, PhantomData));
+    }
+}
+
+struct PostProcessNode<U>(PhantomData<fn() -> U>);
+
+impl<U> Default for PostProcessNode<U> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<U: PostProcess> ViewNode for PostProcessNode<U> {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ViewPostProcessPipeline<U>,
+        Option<&'static PostProcessTexture<U>>,
+        &'static DynamicUniformIndex<U>,
+    );
+
+    fn run<'w>(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (target, pipeline, texture, uniform_index): QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let post_process_pipeline = world.resource::<PostProcessPipeline<U>>();
+        let images = world.resource::<RenderAssets<Image>>();
+        let uniforms = world.resource::<ComponentUniforms<U>>();
+
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(pipeline.0) else {
+            return Ok(());
+        };
+        let texture = match texture {
+            Some(texture) => match images.get(&texture.0) {
+                Some(image) => Some(image),
+                None => return Ok(()),
+            },
+            None if U::TEXTURE => return Ok(()),
+            None => None,
+        };
+        let Some(uniforms) = uniforms.binding() else {
+            return Ok(());
+        };
+
+        let post_process = target.post_process_write();
+        let label = format!("{}_bind_group", U::NAME);
+        let bind_group = match texture {
+            Some(texture) if U::TEXTURE => render_context.render_device().create_bind_group(
+                label.as_str(),
+                &post_process_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    post_process.source,
+                    &post_process_pipeline.sampler,
+                    &texture.texture_view,
+                    uniforms,
+                )),
+            ),
+            _ => render_context.render_device().create_bind_group(
+                label.as_str(),
+                &post_process_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    post_process.source,
+                    &post_process_pipeline.sampler,
+                    uniforms,
+                )),
+            ),
+        };
+
+        let pass_label = format!("{}_pass", U::NAME);
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some(pass_label.as_str()),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
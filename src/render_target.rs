@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{
+    Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+use bevy::render::texture::ImageSampler;
+
+/// Create an image that can be used as the render target of a camera.
+pub(crate) fn render_target_image(size: UVec2, sampler: ImageSampler) -> Image {
+    let size = Extent3d {
+        width: size.x.max(1),
+        height: size.y.max(1),
+        ..Default::default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        sampler,
+        ..Default::default()
+    };
+    // Fill the image data with zeroes.
+    image.resize(size);
+    image
+}
+
+/// Resize a render target image, if its size differs from `size`.
+pub(crate) fn resize_render_target(images: &mut Assets<Image>, image: &Handle<Image>, size: UVec2) {
+    let size = Extent3d {
+        width: size.x.max(1),
+        height: size.y.max(1),
+        ..Default::default()
+    };
+    let needs_resize = images
+        .get(image)
+        .is_some_and(|image| image.texture_descriptor.size != size);
+    if needs_resize {
+        if let Some(image) = images.get_mut(image) {
+            image.resize(size);
+        }
+    }
+}
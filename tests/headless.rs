@@ -0,0 +1,806 @@
+//! Check the camera setup without a window or GPU, as a game would in CI.
+
+use std::time::Duration;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::render::camera::{ManualTextureViewHandle, RenderTarget, ScalingMode};
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{WindowRef, WindowResolution};
+use bevy_pixel_camera::math::{compute_viewport, compute_zoom};
+use bevy_pixel_camera::{
+    resize_headless_window, set_headless_scale_factor, spawn_headless_window, DefaultPixelZoom,
+    PixelBackground, PixelCamera, PixelCameraCommands, PixelCameraConfig,
+    PixelCameraHeadlessPlugin, PixelCameraPlugin, PixelCanvas, PixelCanvasPlugin, PixelCursor,
+    PixelCursorPlugin, PixelHiResOverlay, PixelLetterboxColor, PixelMinimap, PixelSnap,
+    PixelSplitScreen, PixelTargetSize, PixelViewport, PixelZoom, PixelZoomEasing,
+    PixelZoomHysteresis, PixelZoomTransition, QuantizedRotation, SplitScreenLayout, SubpixelOffset,
+    ViewportAlignment, VirtualCursor, VirtualTouches,
+};
+
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        PixelCameraHeadlessPlugin,
+        PixelCameraPlugin::default(),
+    ));
+    app
+}
+
+fn scaling_mode(app: &App, camera: Entity) -> ScalingMode {
+    app.world
+        .get::<OrthographicProjection>(camera)
+        .unwrap()
+        .scaling_mode
+}
+
+#[test]
+fn cameras_match_the_pure_functions() {
+    let mut app = headless_app();
+    let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let zoom_mode = PixelZoom::FitSize {
+        width: 320,
+        height: 180,
+    };
+    let camera = app
+        .world
+        .spawn((
+            Camera2dBundle::default(),
+            zoom_mode.clone(),
+            PixelViewport::default(),
+        ))
+        .id();
+    app.update();
+
+    for size in [
+        Vec2::new(800.0, 600.0),
+        Vec2::new(1920.0, 1080.0),
+        Vec2::new(500.0, 900.0),
+    ] {
+        resize_headless_window(&mut app.world, window, size.x, size.y);
+        app.update();
+
+        let zoom = compute_zoom(&zoom_mode, size);
+        let ScalingMode::WindowSize(camera_zoom) = scaling_mode(&app, camera) else {
+            panic!("unexpected scaling mode");
+        };
+        assert_eq!(camera_zoom, zoom);
+
+        let viewport = compute_viewport(
+            &zoom_mode,
+            &PixelViewport::default(),
+            zoom,
+            size.as_uvec2(),
+            size,
+        );
+        let camera_viewport = app.world.get::<Camera>(camera).unwrap().viewport.clone();
+        let camera_viewport = camera_viewport.unwrap();
+        assert_eq!(
+            camera_viewport.physical_position,
+            viewport.physical_position
+        );
+        assert_eq!(camera_viewport.physical_size, viewport.physical_size);
+    }
+}
+
+#[test]
+fn each_camera_uses_its_own_zoom() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 1280.0, 720.0);
+    let fixed = app
+        .world
+        .spawn((Camera2dBundle::default(), PixelZoom::Fixed(3)))
+        .id();
+    let fit = app
+        .world
+        .spawn((Camera2dBundle::default(), PixelZoom::FitHeight(180)))
+        .id();
+    app.update();
+
+    assert!(matches!(scaling_mode(&app, fixed), ScalingMode::WindowSize(zoom) if zoom == 3.0));
+    assert!(matches!(scaling_mode(&app, fit), ScalingMode::WindowSize(zoom) if zoom == 4.0));
+}
+
+#[test]
+fn spawn_pixel_camera_adds_the_components() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 1280.0, 720.0);
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn_pixel_camera(PixelCameraConfig::fit(320, 180).centered());
+    });
+    app.update();
+
+    let mut cameras = app
+        .world
+        .query::<(&Camera, &OrthographicProjection, &PixelViewport)>();
+    let (camera, projection, viewport) = cameras.single(&app.world);
+    assert_eq!(viewport.alignment, ViewportAlignment::Center);
+    assert!(matches!(projection.scaling_mode, ScalingMode::WindowSize(zoom) if zoom == 4.0));
+    let camera_viewport = camera.viewport.as_ref().unwrap();
+    assert_eq!(camera_viewport.physical_size, UVec2::new(1280, 720));
+}
+
+#[test]
+fn set_letterbox_color_colors_the_bars_only() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn(PixelCamera::fit(320, 180))
+        .insert(Camera {
+            clear_color: ClearColorConfig::Custom(Color::MIDNIGHT_BLUE),
+            ..Default::default()
+        })
+        .id();
+    app.update();
+
+    let set_color =
+        move |color| move |mut commands: Commands| commands.set_letterbox_color(camera, color);
+    app.world.run_system_once(set_color(Color::RED));
+    app.update();
+    let bars = app.world.get::<PixelLetterboxColor>(camera).unwrap();
+    assert_eq!(bars.color, Color::RED);
+
+    // An existing `PixelLetterboxColor` is updated.
+    app.world.run_system_once(set_color(Color::GREEN));
+    app.update();
+    let bars = app.world.get::<PixelLetterboxColor>(camera).unwrap();
+    assert_eq!(bars.color, Color::GREEN);
+
+    // The play area keeps its own clear color.
+    let camera = app.world.get::<Camera>(camera).unwrap();
+    assert!(matches!(
+        camera.clear_color,
+        ClearColorConfig::Custom(color) if color == Color::MIDNIGHT_BLUE
+    ));
+}
+
+#[test]
+fn commands_update_cameras_as_the_plugin_does() {
+    let mut app = headless_app();
+    app.init_asset::<Shader>()
+        .init_asset::<Mesh>()
+        .add_plugins(PixelCanvasPlugin)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+    let window = spawn_headless_window(&mut app.world, 990.0, 600.0);
+    let canvas = app
+        .world
+        .spawn((PixelCamera::fit(320, 180), PixelCanvas::default()))
+        .id();
+    let hysteresis = app
+        .world
+        .spawn((PixelCamera::fit(320, 180), PixelZoomHysteresis(20.0)))
+        .id();
+    let transition = app
+        .world
+        .spawn((
+            PixelCamera::fit(320, 180),
+            PixelZoomTransition::new(1.0, PixelZoomEasing::Linear),
+        ))
+        .id();
+    app.update();
+    // Under the threshold of zoom 3 (960), but inside the hysteresis band.
+    resize_headless_window(&mut app.world, window, 950.0, 600.0);
+    app.update();
+    while app
+        .world
+        .get::<PixelZoomTransition>(transition)
+        .unwrap()
+        .is_animating()
+    {
+        app.update();
+    }
+    let zoom = |app: &App, camera| match scaling_mode(app, camera) {
+        ScalingMode::WindowSize(zoom) => zoom,
+        _ => panic!("unexpected scaling mode"),
+    };
+    assert_eq!(zoom(&app, hysteresis), 3.0);
+    assert_eq!(zoom(&app, transition), 2.0);
+
+    app.world.run_system_once(move |mut commands: Commands| {
+        commands.set_target_resolution(canvas, 160, 90);
+        commands.set_zoom_mode(
+            hysteresis,
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+        );
+        commands.set_target_resolution(transition, 160, 90);
+    });
+
+    // The canvas is still rendered at one texel per virtual pixel.
+    assert_eq!(zoom(&app, canvas), 1.0);
+    // The hysteresis band is still taken into account.
+    assert_eq!(zoom(&app, hysteresis), 3.0);
+    // The new zoom of the transition is animated.
+    let animating = |app: &App| {
+        app.world
+            .get::<PixelZoomTransition>(transition)
+            .unwrap()
+            .is_animating()
+    };
+    assert!(animating(&app));
+    assert_eq!(zoom(&app, transition), 2.0);
+    while animating(&app) {
+        app.update();
+    }
+    assert_eq!(zoom(&app, transition), 5.0);
+}
+
+#[test]
+fn each_window_configures_its_own_cameras() {
+    let mut app = headless_app();
+    let primary = spawn_headless_window(&mut app.world, 1280.0, 720.0);
+    let secondary = app
+        .world
+        .spawn(Window {
+            resolution: WindowResolution::new(400.0, 400.0),
+            ..Default::default()
+        })
+        .id();
+    let zoom_mode = PixelZoom::FitSize {
+        width: 100,
+        height: 100,
+    };
+    let primary_camera = app
+        .world
+        .spawn((
+            Camera2dBundle::default(),
+            zoom_mode.clone(),
+            PixelViewport::default(),
+        ))
+        .id();
+    let secondary_camera = app
+        .world
+        .spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    target: RenderTarget::Window(WindowRef::Entity(secondary)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            zoom_mode,
+            PixelViewport::default(),
+        ))
+        .id();
+    app.update();
+    let viewport_size = |app: &App, camera: Entity| {
+        let camera = app.world.get::<Camera>(camera).unwrap();
+        camera.viewport.as_ref().unwrap().physical_size
+    };
+    assert!(
+        matches!(scaling_mode(&app, primary_camera), ScalingMode::WindowSize(zoom) if zoom == 7.0)
+    );
+    assert!(
+        matches!(scaling_mode(&app, secondary_camera), ScalingMode::WindowSize(zoom) if zoom == 4.0)
+    );
+
+    // Only the camera of the resized window is affected.
+    resize_headless_window(&mut app.world, secondary, 200.0, 300.0);
+    app.update();
+    assert!(
+        matches!(scaling_mode(&app, primary_camera), ScalingMode::WindowSize(zoom) if zoom == 7.0)
+    );
+    assert!(
+        matches!(scaling_mode(&app, secondary_camera), ScalingMode::WindowSize(zoom) if zoom == 2.0)
+    );
+    assert_eq!(viewport_size(&app, secondary_camera), UVec2::new(200, 200));
+
+    // Each window has its own scale factor.
+    set_headless_scale_factor(&mut app.world, secondary, 2.0);
+    app.update();
+    assert_eq!(viewport_size(&app, primary_camera), UVec2::new(700, 700));
+    assert_eq!(viewport_size(&app, secondary_camera), UVec2::new(400, 400));
+
+    resize_headless_window(&mut app.world, primary, 640.0, 360.0);
+    app.update();
+    assert_eq!(viewport_size(&app, primary_camera), UVec2::new(300, 300));
+    assert_eq!(viewport_size(&app, secondary_camera), UVec2::new(400, 400));
+}
+
+#[test]
+fn split_screen_cameras_share_their_zoom() {
+    let mut app = headless_app();
+    let window = spawn_headless_window(&mut app.world, 1280.0, 720.0);
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn_split_screen(
+            SplitScreenLayout::SideBySide,
+            [
+                PixelCameraConfig::fit(320, 180),
+                PixelCameraConfig::fit(160, 90),
+            ],
+        );
+    });
+    app.update();
+
+    let cameras = |app: &mut App| {
+        let mut query = app
+            .world
+            .query::<(&Camera, &OrthographicProjection, &PixelSplitScreen)>();
+        let mut cameras: Vec<_> = query
+            .iter(&app.world)
+            .map(|(camera, projection, _)| {
+                let viewport = camera.viewport.as_ref().unwrap();
+                (
+                    camera.order,
+                    projection.scaling_mode,
+                    viewport.physical_position,
+                    viewport.physical_size,
+                )
+            })
+            .collect();
+        cameras.sort_by_key(|(order, ..)| *order);
+        cameras
+    };
+    let zoom = |scaling_mode: ScalingMode| match scaling_mode {
+        ScalingMode::WindowSize(zoom) => zoom,
+        _ => panic!("unexpected scaling mode"),
+    };
+
+    let cameras_before = cameras(&mut app);
+    assert_eq!(cameras_before.len(), 2);
+    for (_, scaling_mode, ..) in &cameras_before {
+        assert_eq!(zoom(*scaling_mode), 2.0);
+    }
+    assert_eq!(cameras_before[0].2, UVec2::new(0, 180));
+    assert_eq!(cameras_before[0].3, UVec2::new(640, 360));
+    assert_eq!(cameras_before[1].2, UVec2::new(800, 270));
+    assert_eq!(cameras_before[1].3, UVec2::new(320, 180));
+
+    resize_headless_window(&mut app.world, window, 1920.0, 1080.0);
+    app.update();
+    for (_, scaling_mode, ..) in cameras(&mut app) {
+        assert_eq!(zoom(scaling_mode), 3.0);
+    }
+}
+
+#[test]
+fn hires_overlay_follows_the_viewport() {
+    let mut app = headless_app();
+    let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn((PixelCamera::fit(320, 180), PixelHiResOverlay::default()))
+        .id();
+    app.update();
+    app.update();
+
+    let viewports = |app: &App| {
+        let overlay = app.world.get::<PixelHiResOverlay>(camera).unwrap();
+        let overlay = overlay.camera().unwrap();
+        let viewport = |entity| {
+            let camera = app.world.get::<Camera>(entity).unwrap();
+            camera.viewport.as_ref().unwrap().physical_size
+        };
+        (viewport(camera), viewport(overlay))
+    };
+    let (pixel, overlay) = viewports(&app);
+    assert_eq!(pixel, UVec2::new(640, 360));
+    assert_eq!(overlay, pixel);
+
+    resize_headless_window(&mut app.world, window, 1280.0, 720.0);
+    app.update();
+    let (pixel, overlay) = viewports(&app);
+    assert_eq!(pixel, UVec2::new(1280, 720));
+    assert_eq!(overlay, pixel);
+
+    let overlay = app
+        .world
+        .get::<PixelHiResOverlay>(camera)
+        .unwrap()
+        .camera()
+        .unwrap();
+    app.world.entity_mut(camera).remove::<PixelHiResOverlay>();
+    app.update();
+    assert!(app.world.get_entity(overlay).is_none());
+}
+
+#[test]
+fn minimap_image_fits_the_region() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    app.add_systems(
+        Startup,
+        |mut commands: Commands, mut images: ResMut<Assets<Image>>| {
+            let minimap = PixelMinimap::new(Rect::new(-100.0, -50.0, 301.0, 150.0), 4);
+            let (camera, image) = commands.spawn_minimap(minimap, &mut images);
+            commands.insert_resource(Minimap { camera, image });
+        },
+    );
+    app.update();
+
+    let minimap = app.world.resource::<Minimap>();
+    let image = app.world.resource::<Assets<Image>>().get(&minimap.image);
+    assert_eq!(image.unwrap().size(), UVec2::new(101, 50));
+    let camera = minimap.camera;
+    let translation = app.world.get::<Transform>(camera).unwrap().translation;
+    assert_eq!(translation.truncate(), Vec2::new(102.0, 50.0));
+
+    app.world.get_mut::<PixelMinimap>(camera).unwrap().scale = 2;
+    app.update();
+    let minimap = app.world.resource::<Minimap>();
+    let image = app.world.resource::<Assets<Image>>().get(&minimap.image);
+    assert_eq!(image.unwrap().size(), UVec2::new(201, 100));
+}
+
+#[derive(Resource)]
+struct Minimap {
+    camera: Entity,
+    image: Handle<Image>,
+}
+
+#[test]
+fn default_zoom_skips_the_helper_cameras() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    app.insert_resource(DefaultPixelZoom::new(PixelZoom::Fixed(2)));
+    let camera = app
+        .world
+        .spawn((
+            PixelCamera::fit(320, 180),
+            PixelLetterboxColor::new(Color::BLACK),
+            PixelHiResOverlay::default(),
+            PixelBackground::new(Color::DARK_GRAY),
+        ))
+        .id();
+    app.update();
+    app.update();
+
+    let mut cameras = app.world.query_filtered::<Entity, With<Camera>>();
+    let cameras: Vec<_> = cameras.iter(&app.world).collect();
+    assert_eq!(cameras.len(), 4);
+    for entity in cameras {
+        assert_eq!(
+            app.world.get::<PixelZoom>(entity).is_some(),
+            entity == camera
+        );
+    }
+}
+
+#[test]
+fn background_is_rendered_first() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn((
+            PixelCamera::fit(320, 180),
+            PixelHiResOverlay::default(),
+            PixelBackground::new(Color::DARK_GRAY),
+        ))
+        .id();
+    app.update();
+    app.update();
+
+    let background = app.world.get::<PixelBackground>(camera).unwrap();
+    let background = background.camera().unwrap();
+    let overlay = app.world.get::<PixelHiResOverlay>(camera).unwrap();
+    let overlay = overlay.camera().unwrap();
+    let order = |entity| app.world.get::<Camera>(entity).unwrap().order;
+    assert_eq!(order(background), -1);
+    assert_eq!(order(camera), 0);
+    assert_eq!(order(overlay), 2);
+    let background_camera = app.world.get::<Camera>(background).unwrap();
+    assert!(background_camera.viewport.is_none());
+
+    // The background stays below the other cameras.
+    app.world.spawn(Camera2dBundle {
+        camera: Camera {
+            order: -5,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    app.update();
+    assert_eq!(app.world.get::<Camera>(background).unwrap().order, -6);
+}
+
+#[test]
+fn texture_view_targets_use_the_supplied_size() {
+    let mut app = headless_app();
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    target: RenderTarget::TextureView(ManualTextureViewHandle(7)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            PixelViewport::default(),
+            PixelTargetSize::new(UVec2::new(1280, 720)),
+        ))
+        .id();
+    app.update();
+    assert!(matches!(scaling_mode(&app, camera), ScalingMode::WindowSize(zoom) if zoom == 4.0));
+
+    // The size can be updated at any time.
+    app.world
+        .get_mut::<PixelTargetSize>(camera)
+        .unwrap()
+        .physical = UVec2::new(1600, 1200);
+    app.update();
+    assert!(matches!(scaling_mode(&app, camera), ScalingMode::WindowSize(zoom) if zoom == 5.0));
+    let viewport = app.world.get::<Camera>(camera).unwrap().viewport.clone();
+    let viewport = viewport.unwrap();
+    assert_eq!(viewport.physical_position, UVec2::new(0, 150));
+    assert_eq!(viewport.physical_size, UVec2::new(1600, 900));
+}
+
+/// A window of 800 x 600 with a 320 x 180 camera, zoomed 2x in a viewport at
+/// (80, 120).
+#[test]
+fn optional_systems_can_be_disabled() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        PixelCameraHeadlessPlugin,
+        PixelCameraPlugin {
+            camera_effects: false,
+            virtual_cursor: false,
+            snapping: false,
+            letterbox: false,
+            overlays: false,
+            split_screen: false,
+            supersample: false,
+            alignment_warnings: false,
+            ..Default::default()
+        },
+    ));
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn((
+            PixelCamera::fit(320, 180),
+            PixelBackground::new(Color::DARK_GRAY),
+        ))
+        .id();
+    app.update();
+    app.update();
+
+    // The zoom is still computed, without the optional parts.
+    assert!(matches!(
+        scaling_mode(&app, camera),
+        ScalingMode::WindowSize(zoom) if zoom == 2.0
+    ));
+    assert!(app
+        .world
+        .get::<PixelBackground>(camera)
+        .unwrap()
+        .camera()
+        .is_none());
+    assert!(!app.world.contains_resource::<VirtualCursor>());
+}
+
+fn letterboxed_app() -> (App, Entity, Entity) {
+    let mut app = headless_app();
+    let window = spawn_headless_window(&mut app.world, 800.0, 600.0);
+    let camera = app
+        .world
+        .spawn((
+            Camera2dBundle::default(),
+            PixelZoom::FitSize {
+                width: 320,
+                height: 180,
+            },
+            PixelViewport::default(),
+        ))
+        .id();
+    // Bevy updates the projection matrix the frame after the zoom is set.
+    app.update();
+    app.update();
+    (app, window, camera)
+}
+
+#[test]
+fn virtual_cursor_accounts_for_the_viewport() {
+    let (mut app, window, camera) = letterboxed_app();
+
+    let set_cursor = |app: &mut App, position: Vec2| {
+        let mut window = app.world.get_mut::<Window>(window).unwrap();
+        window.set_cursor_position(Some(position));
+        app.update();
+        *app.world.resource::<VirtualCursor>()
+    };
+
+    // The center of the top-left virtual pixel of the viewport.
+    let cursor = set_cursor(&mut app, Vec2::new(81.0, 121.0));
+    assert_eq!(cursor.camera, Some(camera));
+    let world = cursor.world.unwrap();
+    assert!(world.abs_diff_eq(Vec2::new(-159.5, 89.5), 1.0e-3));
+    assert_eq!(cursor.virtual_pixel, Some(IVec2::new(-160, 89)));
+
+    // Two logical pixels are one virtual pixel.
+    let cursor = set_cursor(&mut app, Vec2::new(403.0, 297.0));
+    assert_eq!(cursor.virtual_pixel, Some(IVec2::new(1, 1)));
+
+    // Over the letterbox bars.
+    let cursor = set_cursor(&mut app, Vec2::new(40.0, 60.0));
+    assert_eq!(cursor, VirtualCursor::default());
+}
+
+#[test]
+fn os_cursor_is_shown_again_without_pixel_cursors() {
+    let (mut app, window, _) = letterboxed_app();
+    app.add_plugins(PixelCursorPlugin);
+    let cursor = app
+        .world
+        .spawn((SpriteBundle::default(), PixelCursor::default()))
+        .id();
+    let os_cursor_visible = |app: &App| app.world.get::<Window>(window).unwrap().cursor.visible;
+
+    // Over the viewport.
+    let mut window_component = app.world.get_mut::<Window>(window).unwrap();
+    window_component.set_cursor_position(Some(Vec2::new(403.0, 297.0)));
+    app.update();
+    assert!(!os_cursor_visible(&app));
+
+    app.world.despawn(cursor);
+    app.update();
+    assert!(os_cursor_visible(&app));
+}
+
+#[test]
+fn virtual_touches_account_for_the_viewport() {
+    let (mut app, window, camera) = letterboxed_app();
+    app.add_plugins(InputPlugin);
+
+    for (id, position) in [(1, Vec2::new(81.0, 121.0)), (2, Vec2::new(40.0, 60.0))] {
+        app.world.send_event(TouchInput {
+            phase: TouchPhase::Started,
+            position,
+            window,
+            force: None,
+            id,
+        });
+    }
+    app.update();
+
+    let touches = app.world.resource::<VirtualTouches>();
+    let touch = touches.get(1).unwrap();
+    assert_eq!(touch.camera, camera);
+    assert!(touch.world.abs_diff_eq(Vec2::new(-159.5, 89.5), 1.0e-3));
+    assert_eq!(touch.virtual_pixel, Some(IVec2::new(-160, 89)));
+    // Over the letterbox bars.
+    assert!(touches.get(2).is_none());
+    assert_eq!(touches.iter().count(), 1);
+}
+
+/// A headless app with the transform propagation, and a pixel camera.
+fn snapping_app() -> App {
+    let mut app = headless_app();
+    app.add_plugins(TransformPlugin);
+    spawn_headless_window(&mut app.world, 800.0, 600.0);
+    app.world.spawn(PixelCamera::fit(320, 180));
+    app
+}
+
+/// Run a frame, and return the translations rendered at the end of the frame,
+/// then the translations seen by the game logic of the next frame (i.e. after
+/// `PreUpdate`).
+fn rendered_and_logical<T>(app: &mut App, read: impl Fn(&World) -> T) -> (T, T) {
+    app.update();
+    let rendered = read(&app.world);
+    app.world.run_schedule(PreUpdate);
+    (rendered, read(&app.world))
+}
+
+#[test]
+fn subpixel_offset_is_restored_next_frame() {
+    let mut app = snapping_app();
+    let entity = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(10.25, 20.75, 0.0)),
+            PixelSnap::default(),
+            SubpixelOffset::default(),
+        ))
+        .id();
+    let translations = |world: &World| {
+        (
+            world.get::<Transform>(entity).unwrap().translation,
+            world.get::<GlobalTransform>(entity).unwrap().translation(),
+        )
+    };
+
+    for _ in 0..2 {
+        let (rendered, logical) = rendered_and_logical(&mut app, translations);
+        assert_eq!(rendered.0, Vec3::new(10.0, 21.0, 0.0));
+        assert_eq!(rendered.1, Vec3::new(10.0, 21.0, 0.0));
+        assert_eq!(logical.0, Vec3::new(10.25, 20.75, 0.0));
+    }
+}
+
+#[test]
+fn global_snapping_keeps_the_hierarchy_smooth() {
+    let mut app = snapping_app();
+    // A parent with a fractional position, not snapped itself.
+    let parent = app
+        .world
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            10.25, 0.0, 0.0,
+        )))
+        .id();
+    let child = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(0.5, 0.5, 0.0)),
+            PixelSnap::default().global(),
+        ))
+        .set_parent(parent)
+        .id();
+    let grandchild = app
+        .world
+        .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+            1.0, 0.0, 0.0,
+        )))
+        .set_parent(child)
+        .id();
+    let translations = |world: &World| {
+        let global = |entity| world.get::<GlobalTransform>(entity).unwrap().translation();
+        (
+            world.get::<Transform>(child).unwrap().translation,
+            global(child),
+            global(grandchild),
+        )
+    };
+
+    for _ in 0..2 {
+        let (rendered, logical) = rendered_and_logical(&mut app, translations);
+        // The `Transform` is never modified.
+        assert_eq!(rendered.0, Vec3::new(0.5, 0.5, 0.0));
+        assert_eq!(logical.0, Vec3::new(0.5, 0.5, 0.0));
+        // Only the rendered `GlobalTransform` is aligned on virtual pixels.
+        assert_eq!(rendered.1, Vec3::new(11.0, 1.0, 0.0));
+        assert_eq!(logical.1, Vec3::new(10.75, 0.5, 0.0));
+        // The children still follow the smooth position.
+        assert_eq!(rendered.2, Vec3::new(11.75, 0.5, 0.0));
+    }
+}
+
+#[test]
+fn quantized_rotation_is_restored_next_frame() {
+    let mut app = snapping_app();
+    // 50 degrees, between the 45 and 67.5 degrees of 16 directions.
+    let angle = 50.0_f32.to_radians();
+    let entity = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_rotation(Quat::from_rotation_z(angle))),
+            QuantizedRotation(16),
+        ))
+        .id();
+    let rotations = |world: &World| {
+        let z = |rotation: Quat| rotation.to_euler(EulerRot::ZYX).0;
+        (
+            z(world.get::<Transform>(entity).unwrap().rotation),
+            z(world
+                .get::<GlobalTransform>(entity)
+                .unwrap()
+                .to_scale_rotation_translation()
+                .1),
+        )
+    };
+
+    for _ in 0..2 {
+        let (rendered, logical) = rendered_and_logical(&mut app, rotations);
+        assert!((rendered.0 - 45.0_f32.to_radians()).abs() < 1.0e-5);
+        assert!((rendered.1 - 45.0_f32.to_radians()).abs() < 1.0e-5);
+        assert!((logical.0 - angle).abs() < 1.0e-5);
+    }
+}